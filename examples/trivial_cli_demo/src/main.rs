@@ -127,7 +127,7 @@ fn main() -> Result<()> {
 
             Ok(NextLoopState::Continue)
         },
-        |e, s, i, canvas, dt| {
+        |e, s, i, canvas, frame| {
             // RENDER BEGIN
             canvas.clear_screen(&Color::from_rgb(0, 0, 0));
 
@@ -72,7 +72,7 @@ fn main() -> Result<()> {
 
             Ok(NextLoopState::Continue)
         },
-        |e, s, i, canvas, dt| {
+        |e, s, i, canvas, frame| {
             let width = canvas.width();
             let height = canvas.height();
 
@@ -3,24 +3,100 @@
 //! This module provides a canvas implementation that renders to the terminal
 //! using crossterm for colored output. It requires the "crossterm" feature
 //! to be enabled. The implementation uses Unicode half blocks for rendering
-//! and supports frame rate limiting.
+//! by default and supports frame rate limiting. Other encodings provide a
+//! higher resolution using quadrant blocks, sextants or braille patterns (See
+//! [TerminalEncoding]).
+//!
+//! Besides pixels, real terminal characters can be placed on top of the
+//! canvas (See [CrosstermCanvas::print]), which keeps text readable at
+//! terminal resolution.
+//!
+//! The terminal is restored to its original state, even if the loop is left
+//! due to an error or a panic. For panics this happens before the panic
+//! message is printed, so that it is readable.
 
 use super::{Canvas, RenderableCanvas};
 use crate::color::Color;
-use crate::input::CrosstermInputState;
-use anyhow::Result;
-use crossterm::event::Event;
+use crate::input::crossterm::KEYBOARD_ENHANCEMENT_PUSHED;
+use crate::input::{CrosstermInputState, InputState};
+use crate::Game;
+use anyhow::{Context, Result};
+use crossterm::event::{DisableMouseCapture, Event, PopKeyboardEnhancementFlags};
 use crossterm::style::{self, Print, SetColors};
-use crossterm::{cursor, ExecutableCommand};
+use crossterm::terminal::LeaveAlternateScreen;
+use crossterm::{cursor, execute, ExecutableCommand};
 use std::io::Write;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
 use std::time::{Duration, Instant};
 
+/// Whether the terminal is currently set up by a [CrosstermCanvas], and
+/// therefore needs to be restored if the loop is left unexpectedly.
+static TERMINAL_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+static INSTALL_PANIC_HOOK: Once = Once::new();
+
+/// Restores the terminal to its original state, if it is currently set up by
+/// a [CrosstermCanvas].
+///
+/// Keyboard enhancement flags are popped, mouse capture is disabled, the
+/// alternate screen is left and raw mode is disabled. Errors are ignored, as
+/// this is a last resort, which might be called while panicking.
+fn restore_terminal() {
+    if !TERMINAL_ACTIVE.swap(false, Ordering::SeqCst) {
+        return;
+    }
+
+    let mut stdout = std::io::stdout();
+    if KEYBOARD_ENHANCEMENT_PUSHED.swap(false, Ordering::SeqCst) {
+        let _ = execute!(stdout, PopKeyboardEnhancementFlags);
+    }
+    let _ = execute!(
+        stdout,
+        DisableMouseCapture,
+        LeaveAlternateScreen,
+        cursor::Show
+    );
+    let _ = crossterm::terminal::disable_raw_mode();
+}
+
+/// Marks the terminal as set up and makes sure it is restored on panic.
+///
+/// The panic hook is installed only once per process. It restores the
+/// terminal before calling the previously installed hook, which usually
+/// prints the panic message.
+fn activate_terminal() {
+    INSTALL_PANIC_HOOK.call_once(|| {
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            restore_terminal();
+            previous_hook(info);
+        }));
+    });
+    TERMINAL_ACTIVE.store(true, Ordering::SeqCst);
+}
+
+/// Restores the terminal once dropped, unless the loop has been finished
+/// properly already.
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
 /// A canvas implementation that renders to the terminal using crossterm.
 ///
 /// This canvas provides terminal-based rendering using Unicode half blocks
 /// and ANSI colors. It supports frame rate limiting and efficient updates
 /// by only redrawing changed parts of the screen.
 ///
+/// By default the canvas is driven by a [CrosstermInputState]. Any input
+/// implementation wrapping it may be used instead (See
+/// [with_input_type](CrosstermCanvas::with_input_type)).
+///
 /// # Example
 /// ```
 /// use pixel_loop::canvas::CrosstermCanvas;
@@ -38,32 +114,38 @@ use std::time::{Duration, Instant};
 ///  Ok(())
 /// }
 /// ```
-pub struct CrosstermCanvas {
-    /// Width of the canvas in pixels (characters)
+pub struct CrosstermCanvas<I = CrosstermInputState> {
+    /// Width of the canvas in pixels
     width: u32,
-    /// Height of the canvas in pixels (half characters)
+    /// Height of the canvas in pixels
     height: u32,
+    /// Characters used to represent the pixels of a terminal cell
+    encoding: TerminalEncoding,
     /// Resizability of the canvas
     resizable: bool,
     /// Current frame buffer
     buffer: Vec<Color>,
     /// Previous frame buffer for change detection
     previous_buffer: Vec<Color>,
+    /// Characters drawn on top of the pixels for every terminal cell
+    overlay: Vec<Option<TerminalCell>>,
+    /// Previous characters for change detection
+    previous_overlay: Vec<Option<TerminalCell>>,
     /// Minimal frame time in nanoseconds
     frame_limit_nanos: u64,
     /// Timestamp of the last rendered frame
     last_frame_time: Instant,
-    /// The width of this canvas during the last loop
-    last_loop_width: u32,
-    /// The height of this canvas during the last loop
-    last_loop_height: u32,
+    /// The input implementation this canvas is driven with
+    input: PhantomData<I>,
 }
 
 impl CrosstermCanvas {
     /// Creates a new terminal canvas automatically toking the size of the
     /// terminal it is spawned in.
     ///
-    /// A canvas based on the terminals size is resizable by default.
+    /// A canvas based on the terminals size is resizable by default. It uses
+    /// [TerminalEncoding::HalfBlock], which can be changed using
+    /// [with_encoding](CrosstermCanvas::with_encoding).
     ///
     /// # Example
     /// ```
@@ -81,8 +163,8 @@ impl CrosstermCanvas {
     /// A canvas with specified dimensions is not resizable by default.
     ///
     /// # Arguments
-    /// * `width` - The width of the canvas in characters
-    /// * `height` - The height of the canvas in half characters
+    /// * `width` - The width of the canvas in pixels
+    /// * `height` - The height of the canvas in pixels
     ///
     /// # Example
     /// ```
@@ -94,17 +176,53 @@ impl CrosstermCanvas {
         let mut canvas = Self {
             width,
             height,
+            encoding: TerminalEncoding::default(),
             resizable: false,
             buffer: vec![],
             previous_buffer: vec![],
+            overlay: vec![],
+            previous_overlay: vec![],
             frame_limit_nanos: 1_000_000_000 / 60,
             last_frame_time: Instant::now(),
-            last_loop_height: 0, // Zero initialized to cause initial update
-            last_loop_width: 0,  // Zero initialized to cause initial update
+            input: PhantomData,
         };
         canvas.resize_surface(width, height, None);
         canvas
     }
+}
+
+impl<I> CrosstermCanvas<I> {
+    /// Changes the input implementation this canvas is driven with.
+    ///
+    /// Terminal events are handed to the wrapped [CrosstermInputState] of the
+    /// new input implementation.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use pixel_loop::canvas::CrosstermCanvas;
+    /// use pixel_loop::input::{CrosstermInputState, RecordingInputState};
+    ///
+    /// let canvas = CrosstermCanvas::new()
+    ///     .with_input_type::<RecordingInputState<CrosstermInputState>>();
+    /// ```
+    pub fn with_input_type<J>(self) -> CrosstermCanvas<J>
+    where
+        J: InputState + AsMut<CrosstermInputState>,
+    {
+        CrosstermCanvas {
+            width: self.width,
+            height: self.height,
+            encoding: self.encoding,
+            resizable: self.resizable,
+            buffer: self.buffer,
+            previous_buffer: self.previous_buffer,
+            overlay: self.overlay,
+            previous_overlay: self.previous_overlay,
+            frame_limit_nanos: self.frame_limit_nanos,
+            last_frame_time: self.last_frame_time,
+            input: PhantomData,
+        }
+    }
 
     /// Sets the canvas to be resizable or not.
     pub fn with_resizable(mut self, resizable: bool) -> Self {
@@ -130,9 +248,154 @@ impl CrosstermCanvas {
         self.frame_limit_nanos = 1_000_000_000u64 / limit as u64;
         self
     }
+
+    /// Sets the characters used to represent the pixels of a terminal cell.
+    ///
+    /// A resizable canvas keeps covering the same terminal cells, therefore
+    /// its size in pixels changes according to the cell size of the
+    /// encoding. Otherwise the size in pixels is kept.
+    ///
+    /// By default [TerminalEncoding::HalfBlock] is used.
+    ///
+    /// # Example
+    /// ```
+    /// use pixel_loop::canvas::crossterm::TerminalEncoding;
+    /// use pixel_loop::canvas::{Canvas, CrosstermCanvas};
+    ///
+    /// let canvas = CrosstermCanvas::new_with_size(80, 48)
+    ///     .with_resizable(true)
+    ///     .with_encoding(TerminalEncoding::Braille);
+    /// assert_eq!((canvas.width(), canvas.height()), (160, 96));
+    /// assert_eq!((canvas.columns(), canvas.rows()), (80, 24));
+    /// ```
+    pub fn with_encoding(mut self, encoding: TerminalEncoding) -> Self {
+        let (width, height) = if self.resizable {
+            let (cell_width, cell_height) = encoding.cell_size();
+            (self.columns() * cell_width, self.rows() * cell_height)
+        } else {
+            (self.width, self.height)
+        };
+        self.encoding = encoding;
+        self.resize_cells(width, height);
+        self
+    }
+
+    /// Get the characters used to represent the pixels of a terminal cell.
+    pub fn encoding(&self) -> TerminalEncoding {
+        self.encoding
+    }
+
+    /// Get the number of terminal columns covered by the canvas.
+    pub fn columns(&self) -> u32 {
+        self.width.div_ceil(self.encoding.cell_size().0)
+    }
+
+    /// Get the number of terminal rows covered by the canvas.
+    pub fn rows(&self) -> u32 {
+        self.height.div_ceil(self.encoding.cell_size().1)
+    }
+
+    /// Places a character on top of the pixels of a terminal cell.
+    ///
+    /// The character stays in place until it is replaced or cleared (See
+    /// [clear_cell](CrosstermCanvas::clear_cell) and
+    /// [clear_text](CrosstermCanvas::clear_text)), or the canvas is resized.
+    /// It is not part of the pixel buffer, so it is neither affected by
+    /// drawing operations nor contained in screenshots. Cells outside of the
+    /// canvas are ignored.
+    pub fn set_cell(&mut self, column: u32, row: u32, cell: TerminalCell) {
+        if column < self.columns() && row < self.rows() {
+            let index = (row * self.columns() + column) as usize;
+            self.overlay[index] = Some(cell);
+        }
+    }
+
+    /// Get the character placed on top of a terminal cell, if any.
+    pub fn cell(&self, column: u32, row: u32) -> Option<&TerminalCell> {
+        if column < self.columns() && row < self.rows() {
+            self.overlay[(row * self.columns() + column) as usize].as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Removes the character placed on top of a terminal cell, showing its
+    /// pixels again.
+    pub fn clear_cell(&mut self, column: u32, row: u32) {
+        if column < self.columns() && row < self.rows() {
+            let index = (row * self.columns() + column) as usize;
+            self.overlay[index] = None;
+        }
+    }
+
+    /// Removes all characters placed on top of the canvas.
+    pub fn clear_text(&mut self) {
+        self.overlay.fill(None);
+    }
+
+    /// Places text on top of the canvas starting at the given terminal cell.
+    ///
+    /// Every character occupies one cell (See
+    /// [set_cell](CrosstermCanvas::set_cell)), therefore characters spanning
+    /// multiple columns are not supported. A newline continues the text in
+    /// the next row at the starting column, other control characters are
+    /// skipped. Text outside of the canvas is clipped.
+    ///
+    /// # Arguments
+    /// * `column` - Terminal column of the first character
+    /// * `row` - Terminal row of the first line
+    /// * `text` - The text to place
+    /// * `foreground` - Color of the characters
+    /// * `background` - Color of the cells behind the characters. If not
+    ///   given, the average color of the pixels covered by each cell is used.
+    ///
+    /// # Example
+    /// ```
+    /// use pixel_loop::canvas::CrosstermCanvas;
+    /// use pixel_loop::color::Color;
+    ///
+    /// let mut canvas = CrosstermCanvas::new_with_size(20, 10);
+    /// let white = Color::from_rgb(255, 255, 255);
+    /// canvas.print(18, 1, "Score\n42", &white, None);
+    ///
+    /// assert_eq!(canvas.cell(19, 1).map(|cell| cell.character), Some('c'));
+    /// assert_eq!(canvas.cell(18, 2).map(|cell| cell.character), Some('4'));
+    /// assert!(canvas.cell(18, 0).is_none());
+    /// ```
+    pub fn print(
+        &mut self,
+        column: i64,
+        row: i64,
+        text: &str,
+        foreground: &Color,
+        background: Option<&Color>,
+    ) {
+        for (line_index, line) in text.split('\n').enumerate() {
+            let y = row + line_index as i64;
+            if y < 0 || y >= self.rows() as i64 {
+                continue;
+            }
+            let characters = line.chars().filter(|character| !character.is_control());
+            for (offset, character) in characters.enumerate() {
+                let x = column + offset as i64;
+                if x < 0 || x >= self.columns() as i64 {
+                    continue;
+                }
+                self.set_cell(
+                    x as u32,
+                    y as u32,
+                    TerminalCell {
+                        character,
+                        foreground: *foreground,
+                        background: background.copied(),
+                    },
+                );
+            }
+        }
+    }
 }
 
-impl Canvas for CrosstermCanvas {
+impl<I> Canvas for CrosstermCanvas<I> {
     fn width(&self) -> u32 {
         self.width as u32
     }
@@ -152,7 +415,188 @@ impl Canvas for CrosstermCanvas {
 
 /// Unicode character representing the upper half block used for drawing half
 /// character height (quadratic) pixels.
-const UNICODE_UPPER_HALF_BLOCK: &str = "▀";
+const UNICODE_UPPER_HALF_BLOCK: char = '▀';
+
+/// Unicode quadrant block characters indexed by the mask of their filled
+/// quadrants (upper left, upper right, lower left, lower right).
+const UNICODE_QUADRANTS: [char; 16] = [
+    ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+];
+
+/// Braille dot bits indexed by the position of a pixel within a 2x4 cell (row
+/// by row).
+const BRAILLE_DOTS: [u32; 8] = [0x01, 0x08, 0x02, 0x10, 0x04, 0x20, 0x40, 0x80];
+
+/// Characters used to represent the pixels of a terminal cell.
+///
+/// Apart from [HalfBlock](TerminalEncoding::HalfBlock), each cell can only
+/// show two colors (foreground and background). They are chosen to represent
+/// the pixels of the cell best, by splitting them into the two groups with the
+/// least deviation from their average color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TerminalEncoding {
+    /// Upper half blocks (`▀`) representing 1x2 pixels per cell
+    #[default]
+    HalfBlock,
+    /// Quadrant blocks (eg. `▚`) representing 2x2 pixels per cell
+    Quadrant,
+    /// Sextant blocks (eg. `🬗`) representing 2x3 pixels per cell. They are
+    /// part of Unicode 13 and might not be supported by every terminal font.
+    Sextant,
+    /// Braille patterns (eg. `⢝`) representing 2x4 pixels per cell. Pixels
+    /// of the brighter color are drawn as dots, while the darker color is
+    /// used as background.
+    Braille,
+}
+
+impl TerminalEncoding {
+    /// Get the number of pixels (columns, rows) represented by a single
+    /// terminal cell.
+    pub fn cell_size(self) -> (u32, u32) {
+        match self {
+            TerminalEncoding::HalfBlock => (1, 2),
+            TerminalEncoding::Quadrant => (2, 2),
+            TerminalEncoding::Sextant => (2, 3),
+            TerminalEncoding::Braille => (2, 4),
+        }
+    }
+
+    /// Character, foreground and background color representing the pixels of
+    /// a cell (row by row).
+    fn encode(self, pixels: &[Color]) -> (char, Color, Color) {
+        if self == TerminalEncoding::HalfBlock {
+            return (UNICODE_UPPER_HALF_BLOCK, pixels[0], pixels[1]);
+        }
+
+        let (mut mask, mut foreground, mut background) = split_colors(pixels);
+        let character = match self {
+            TerminalEncoding::HalfBlock => unreachable!(),
+            TerminalEncoding::Quadrant => UNICODE_QUADRANTS[mask as usize],
+            TerminalEncoding::Sextant => match mask {
+                0 => ' ',
+                21 => '▌',
+                42 => '▐',
+                63 => '█',
+                // Sextants are ordered by their mask, skipping the ones
+                // already covered by half blocks.
+                _ => {
+                    let skipped = (mask > 21) as u32 + (mask > 42) as u32;
+                    char::from_u32(0x1FB00 + mask - 1 - skipped).unwrap()
+                }
+            },
+            TerminalEncoding::Braille => {
+                if luminance(&foreground) < luminance(&background) {
+                    mask ^= (1 << pixels.len()) - 1;
+                    std::mem::swap(&mut foreground, &mut background);
+                }
+                let dots = (0..pixels.len())
+                    .filter(|index| mask & (1 << index) != 0)
+                    .map(|index| BRAILLE_DOTS[index])
+                    .sum::<u32>();
+                match dots {
+                    0 => ' ',
+                    _ => char::from_u32(0x2800 + dots).unwrap(),
+                }
+            }
+        };
+        (character, foreground, background)
+    }
+}
+
+/// Splits the pixels of a cell into two groups, minimizing the squared
+/// distance of every pixel to the average color of its group.
+///
+/// Returns the mask of pixels within the foreground group, as well as the
+/// average foreground and background color. The first pixel always belongs
+/// to the background group.
+fn split_colors(pixels: &[Color]) -> (u32, Color, Color) {
+    let channels = |color: &Color| [color.r as f64, color.g as f64, color.b as f64];
+    let average = |sums: [f64; 3], count: f64| {
+        Color::from_rgb(
+            (sums[0] / count).round() as u8,
+            (sums[1] / count).round() as u8,
+            (sums[2] / count).round() as u8,
+        )
+    };
+
+    let mut best: Option<(f64, u32, Color, Color)> = None;
+    for mask in (0..1_u32 << pixels.len()).step_by(2) {
+        // Sums of the channels, squared channels and number of pixels per
+        // group (background, foreground)
+        let mut sums = [[0.0; 3]; 2];
+        let mut squares = [0.0; 2];
+        let mut counts = [0.0; 2];
+        for (index, pixel) in pixels.iter().enumerate() {
+            let group = (mask >> index & 1) as usize;
+            counts[group] += 1.0;
+            for (channel, value) in channels(pixel).into_iter().enumerate() {
+                sums[group][channel] += value;
+                squares[group] += value * value;
+            }
+        }
+
+        let error: f64 = (0..2)
+            .filter(|&group| counts[group] > 0.0)
+            .map(|group| {
+                let sum_of_squares: f64 = sums[group].iter().map(|sum| sum * sum).sum();
+                squares[group] - sum_of_squares / counts[group]
+            })
+            .sum();
+        if best.is_none_or(|(best_error, ..)| error < best_error - f64::EPSILON) {
+            let background = average(sums[0], counts[0]);
+            let foreground = if counts[1] > 0.0 {
+                average(sums[1], counts[1])
+            } else {
+                background
+            };
+            best = Some((error, mask, foreground, background));
+        }
+    }
+
+    let (_, mask, foreground, background) = best.unwrap();
+    (mask, foreground, background)
+}
+
+/// Perceived brightness of a color.
+fn luminance(color: &Color) -> u32 {
+    299 * color.r as u32 + 587 * color.g as u32 + 114 * color.b as u32
+}
+
+/// A terminal character placed on top of the pixels of a
+/// [CrosstermCanvas].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TerminalCell {
+    /// The character shown within the cell
+    pub character: char,
+    /// Color of the character
+    pub foreground: Color,
+    /// Color of the cell behind the character. If not set, the average color
+    /// of the pixels covered by the cell is used.
+    pub background: Option<Color>,
+}
+
+impl TerminalCell {
+    /// Foreground and background color the cell is drawn with, given the
+    /// pixels it covers.
+    fn colors(&self, pixels: &[Color]) -> (Color, Color) {
+        let background = self.background.unwrap_or_else(|| {
+            let count = pixels.len() as u32;
+            let average = |channel: fn(&Color) -> u8| {
+                (pixels
+                    .iter()
+                    .map(|pixel| channel(pixel) as u32)
+                    .sum::<u32>()
+                    / count) as u8
+            };
+            Color::from_rgb(
+                average(|pixel| pixel.r),
+                average(|pixel| pixel.g),
+                average(|pixel| pixel.b),
+            )
+        });
+        (self.foreground, background)
+    }
+}
 
 /// Represents a region of the screen that needs to be updated.
 ///
@@ -182,57 +626,84 @@ impl Patch {
         Ok(())
     }
 
-    pub fn add_two_row_pixel(&mut self, upper: &Color, lower: &Color) -> Result<()> {
+    fn set_colors(&mut self, foreground: &Color, background: &Color) -> Result<()> {
         if self.previous_colors.is_none()
-            || self.previous_colors.as_ref().unwrap() != &(*upper, *lower)
+            || self.previous_colors.as_ref().unwrap() != &(*foreground, *background)
         {
             self.data.execute(SetColors(style::Colors::new(
                 style::Color::Rgb {
-                    r: upper.r,
-                    g: upper.g,
-                    b: upper.b,
+                    r: foreground.r,
+                    g: foreground.g,
+                    b: foreground.b,
                 },
                 style::Color::Rgb {
-                    r: lower.r,
-                    g: lower.g,
-                    b: lower.b,
+                    r: background.r,
+                    g: background.g,
+                    b: background.b,
                 },
             )))?;
-            self.previous_colors = Some((*upper, *lower));
+            self.previous_colors = Some((*foreground, *background));
         }
-        self.data.execute(Print(UNICODE_UPPER_HALF_BLOCK))?;
+        Ok(())
+    }
+
+    pub fn add_pixels(&mut self, encoding: TerminalEncoding, pixels: &[Color]) -> Result<()> {
+        let (character, foreground, background) = encoding.encode(pixels);
+        self.set_colors(&foreground, &background)?;
+        self.data.execute(Print(character))?;
+        Ok(())
+    }
+
+    pub fn add_character(&mut self, cell: &TerminalCell, pixels: &[Color]) -> Result<()> {
+        let (foreground, background) = cell.colors(pixels);
+        self.set_colors(&foreground, &background)?;
+        self.data.execute(Print(cell.character))?;
         Ok(())
     }
 }
 
-impl CrosstermCanvas {
+impl<I> CrosstermCanvas<I> {
+    /// Collects the pixels covered by a terminal cell (row by row). Pixels
+    /// outside of the canvas are black.
+    fn cell_pixels(&self, buffer: &[Color], column: u32, row: u32, pixels: &mut Vec<Color>) {
+        let (cell_width, cell_height) = self.encoding.cell_size();
+        pixels.clear();
+        for y in row * cell_height..(row + 1) * cell_height {
+            for x in column * cell_width..(column + 1) * cell_width {
+                if x < self.width && y < self.height {
+                    pixels.push(buffer[(y * self.width + x) as usize]);
+                } else {
+                    pixels.push(Color::from_rgb(0, 0, 0));
+                }
+            }
+        }
+    }
+
     fn calculate_patches(&self) -> Result<Vec<Patch>> {
         let mut patches = Vec::new();
         let mut active_patch: Option<Patch> = None;
+        let mut pixels = Vec::with_capacity(8);
+        let mut previous_pixels = Vec::with_capacity(8);
 
-        for y in (0..self.height as usize).step_by(2) {
-            for x in 0..self.width as usize {
-                let y1 = self.buffer[y * self.width as usize + x];
-                let y2 = if self.height % 2 != 0 {
-                    Color::from_rgb(0, 0, 0)
-                } else {
-                    self.buffer[(y + 1) * self.width as usize + x]
-                };
+        for row in 0..self.rows() {
+            for column in 0..self.columns() {
+                self.cell_pixels(&self.buffer, column, row, &mut pixels);
+                self.cell_pixels(&self.previous_buffer, column, row, &mut previous_pixels);
 
-                let py1 = self.previous_buffer[y * self.width as usize + x];
-                let py2 = if self.height % 2 != 0 {
-                    Color::from_rgb(0, 0, 0)
-                } else {
-                    self.previous_buffer[(y + 1) * self.width as usize + x]
-                };
+                let cell = &self.overlay[(row * self.columns() + column) as usize];
+                let previous_cell =
+                    &self.previous_overlay[(row * self.columns() + column) as usize];
 
-                if y1 != py1 || y2 != py2 {
+                if pixels != previous_pixels || cell != previous_cell {
                     if active_patch.is_none() {
-                        active_patch = Some(Patch::new(x as u16, (y / 2) as u16));
+                        active_patch = Some(Patch::new(column as u16, row as u16));
                     }
 
                     let patch = active_patch.as_mut().unwrap();
-                    patch.add_two_row_pixel(&y1, &y2)?;
+                    match cell {
+                        Some(cell) => patch.add_character(cell, &pixels)?,
+                        None => patch.add_pixels(self.encoding, &pixels)?,
+                    }
                 } else if active_patch.is_some() {
                     patches.push(active_patch.take().unwrap());
                 }
@@ -249,6 +720,18 @@ impl CrosstermCanvas {
         Ok(patches)
     }
 
+    /// Reallocates the buffers for the given size in pixels, as well as the
+    /// characters for the resulting number of terminal cells.
+    fn resize_cells(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+        self.buffer = vec![Color::from_rgb(0, 0, 0); width as usize * height as usize];
+        self.previous_buffer = vec![Color::from_rgba(0, 0, 0, 0); width as usize * height as usize];
+        let cells = self.columns() as usize * self.rows() as usize;
+        self.overlay = vec![None; cells];
+        self.previous_overlay = vec![None; cells];
+    }
+
     fn elapsed_since_last_frame(&self) -> u64 {
         // The return value of as_nanos is a u128, but a Duration from_nanos is
         // created with a u64. We are therefore casting this value into a u64 or
@@ -294,8 +777,11 @@ impl CrosstermCanvas {
     }
 }
 
-impl RenderableCanvas for CrosstermCanvas {
-    type Input = CrosstermInputState;
+impl<I> RenderableCanvas for CrosstermCanvas<I>
+where
+    I: InputState + AsMut<CrosstermInputState>,
+{
+    type Input = I;
 
     fn render(&mut self) -> anyhow::Result<()> {
         self.wait_for_next_frame();
@@ -309,27 +795,27 @@ impl RenderableCanvas for CrosstermCanvas {
             patch.apply(&mut buffer)?;
         }
         buffer.execute(cursor::MoveTo(
-            self.width.try_into()?,
-            (self.height / 2).try_into()?,
+            self.columns().try_into()?,
+            self.rows().try_into()?,
         ))?;
         buffer.execute(cursor::Show)?;
         stdout.write_all(&buffer)?;
         stdout.flush()?;
 
         self.previous_buffer.copy_from_slice(&self.buffer);
+        self.previous_overlay.copy_from_slice(&self.overlay);
 
         Ok(())
     }
 
     fn resize_surface(&mut self, width: u32, height: u32, scale_factor: Option<f64>) {
-        self.width = width;
-        self.height = height;
-        self.buffer = vec![Color::from_rgb(0, 0, 0); width as usize * height as usize];
-        self.previous_buffer = vec![Color::from_rgba(0, 0, 0, 0); width as usize * height as usize];
+        self.resize_cells(width, height);
     }
 
-    /// Runs the pixel loop.
-    fn run<State: 'static>(mut pixel_loop: crate::PixelLoop<State, Self>) -> ! {
+    /// Runs the pixel loop until it is exited.
+    fn run_to_completion<G: Game<Self> + 'static>(
+        mut pixel_loop: crate::PixelLoop<G, Self>,
+    ) -> Result<(i32, G)> {
         fn get_all_next_crossterm_events() -> Result<Vec<Event>> {
             use crossterm::event::{poll, read};
             let mut events = vec![];
@@ -345,45 +831,57 @@ impl RenderableCanvas for CrosstermCanvas {
             Ok(events)
         }
 
-        pixel_loop.begin().expect("begin pixel_loop");
-        loop {
-            for event in get_all_next_crossterm_events().expect("get_all_next_crossterm_events") {
-                // Handle resizeing of the terminal
-                if let Event::Resize(columns, rows) = event {
-                    pixel_loop
-                        .canvas
-                        .resize_surface(columns as u32, rows as u32 * 2, None);
+        // Input is set up before the canvas, therefore the terminal needs
+        // to be restored, even if beginning the loop fails.
+        activate_terminal();
+        let _guard = TerminalGuard;
+
+        pixel_loop.begin().context("begin pixel_loop")?;
+        let cell_size = pixel_loop.canvas.encoding.cell_size();
+        pixel_loop.input_state.as_mut().set_cell_size(cell_size);
+        let mut run = || -> Result<i32> {
+            loop {
+                for event in
+                    get_all_next_crossterm_events().context("get_all_next_crossterm_events")?
+                {
+                    // Handle resizeing of the terminal
+                    if let Event::Resize(columns, rows) = event {
+                        pixel_loop.canvas.resize_surface(
+                            columns as u32 * cell_size.0,
+                            rows as u32 * cell_size.1,
+                            None,
+                        );
+                    }
+
+                    // Move elements to input state handler
+                    pixel_loop.input_state.as_mut().handle_new_event(event);
                 }
 
-                // Move elements to input state handler
-                pixel_loop.input_state.handle_new_event(event);
+                let next = pixel_loop.next_loop().context("next_loop pixel_loop")?;
+                if let crate::NextLoopState::Exit(code) = next {
+                    return Ok(code);
+                }
             }
+        };
 
-            let next = pixel_loop.next_loop().expect("next_loop pixel_loop");
-            if let crate::NextLoopState::Exit(code) = next {
-                pixel_loop.finish(code).expect("finish pixel loop");
+        match run() {
+            Ok(code) => {
+                pixel_loop.finish(code).context("finish pixel loop")?;
+                Ok((code, pixel_loop.into_game()))
             }
-            // Track last communicated canvas size
-            pixel_loop.canvas.last_loop_width = pixel_loop.canvas.width();
-            pixel_loop.canvas.last_loop_height = pixel_loop.canvas.height();
-        }
-    }
-
-    fn did_resize(&self) -> Option<(u32, u32)> {
-        if self.last_loop_width != self.width() || self.last_loop_height != self.height() {
-            Some((self.width(), self.height()))
-        } else {
-            None
+            Err(error) => Err(pixel_loop.abort(error)),
         }
     }
 
     fn begin(&mut self) -> Result<()> {
+        activate_terminal();
         std::io::stdout().execute(crossterm::terminal::EnterAlternateScreen)?;
         Ok(())
     }
 
     fn finish(&mut self, _code: i32) -> Result<()> {
         std::io::stdout().execute(crossterm::terminal::LeaveAlternateScreen)?;
+        TERMINAL_ACTIVE.store(false, Ordering::SeqCst);
         Ok(())
     }
 }
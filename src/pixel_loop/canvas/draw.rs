@@ -0,0 +1,436 @@
+//! Rasterization of the shape primitives provided by the [Canvas] trait.
+//!
+//! Shapes are broken down into horizontal spans and single pixels, which are
+//! clipped using [Canvas::clip_rect]. Every pixel of a shape is drawn exactly
+//! once, so blending (eg. [BlendMode::Alpha]) does not darken overlapping
+//! parts, like the vertices of a polygon outline.
+
+use super::Canvas;
+use crate::color::{BlendMode, Color};
+
+/// Draw the pixels from `x0` to `x1` (inclusive) in row `y`.
+pub(crate) fn span<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    x0: i64,
+    x1: i64,
+    y: i64,
+    color: &Color,
+    blend: BlendMode,
+) {
+    // Limit the span to the canvas first, so its width can not overflow.
+    let right_edge = canvas.width() as i64;
+    let (x0, x1) = (x0.min(x1).max(-1), x0.max(x1).min(right_edge));
+    if x1 < 0 || x0 >= right_edge {
+        return;
+    }
+    let width = (x1 - x0 + 1) as u32;
+    if let Some((x, y, width, height)) = canvas.clip_rect(x0, y, width, 1) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        let start = (y * canvas.width() + x) as usize;
+        canvas.set_range_blended(
+            start..start + width as usize,
+            &vec![*color; width as usize],
+            blend,
+        );
+    }
+}
+
+/// Draw the given pixels, skipping duplicates.
+pub(crate) fn points<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    mut points: Vec<(i64, i64)>,
+    color: &Color,
+    blend: BlendMode,
+) {
+    points.sort_unstable_by_key(|&(x, y)| (y, x));
+    points.dedup();
+    for (x, y) in points {
+        span(canvas, x, x, y, color, blend);
+    }
+}
+
+/// Inclusive bounds (min x, min y, max x, max y) of the pixels of a canvas.
+pub(crate) fn canvas_bounds<C: Canvas + ?Sized>(canvas: &C) -> (i64, i64, i64, i64) {
+    (0, 0, canvas.width() as i64 - 1, canvas.height() as i64 - 1)
+}
+
+/// A line between two pixels, which is rasterized by stepping along its major
+/// axis, restricted to the steps within some bounds.
+///
+/// After `i` steps the line has moved `i * minor_delta / major_delta` pixels
+/// (rounded) along its minor axis, like a line drawn with Bresenham's
+/// algorithm. Calculations are done in 128 bit, so any two pixels can be
+/// connected without overflowing.
+pub(crate) struct LineSteps {
+    /// Whether x is the major axis
+    x_major: bool,
+    /// Start pixel as (major, minor) coordinate
+    start: (i128, i128),
+    /// Direction of the line along the (major, minor) axis
+    signs: (i128, i128),
+    /// Absolute length of the line along the major axis
+    major_delta: u128,
+    /// Absolute length of the line along the minor axis
+    minor_delta: u128,
+    /// First step within the bounds
+    pub(crate) first: u128,
+    /// Last step within the bounds
+    pub(crate) last: u128,
+}
+
+impl LineSteps {
+    /// Pixel reached after the given number of steps.
+    pub(crate) fn point(&self, step: u128) -> (i64, i64) {
+        let offset = (step * self.minor_delta + self.major_delta / 2) / self.major_delta;
+        let major = self.start.0 + self.signs.0 * step as i128;
+        let minor = self.start.1 + self.signs.1 * offset as i128;
+        if self.x_major {
+            (major as i64, minor as i64)
+        } else {
+            (minor as i64, major as i64)
+        }
+    }
+
+    /// Pixels of all steps within the bounds.
+    pub(crate) fn points(&self) -> Vec<(i64, i64)> {
+        (self.first..=self.last)
+            .map(|step| self.point(step))
+            .collect()
+    }
+}
+
+/// Restricts the line between two pixels (inclusive) to the steps, whose
+/// pixels lie within the given inclusive bounds (min x, min y, max x, max y).
+///
+/// Returns `None`, if no pixel of the line is within the bounds.
+pub(crate) fn clip_line(
+    (x0, y0): (i64, i64),
+    (x1, y1): (i64, i64),
+    (min_x, min_y, max_x, max_y): (i64, i64, i64, i64),
+) -> Option<LineSteps> {
+    let (dx, dy) = (x1 as i128 - x0 as i128, y1 as i128 - y0 as i128);
+    let x_major = dx.abs() >= dy.abs();
+    let ((major, major_d, major_min, major_max), (minor, minor_d, minor_min, minor_max)) =
+        if x_major {
+            ((x0, dx, min_x, max_x), (y0, dy, min_y, max_y))
+        } else {
+            ((y0, dy, min_y, max_y), (x0, dx, min_x, max_x))
+        };
+    let (major, minor) = (major as i128, minor as i128);
+    if major_min > major_max || minor_min > minor_max {
+        return None;
+    }
+
+    // Range of offsets from the start within the bounds for an axis, in
+    // direction of the line
+    let offsets = |start: i128, sign: i128, min: i64, max: i64| {
+        if sign < 0 {
+            (start - max as i128, start - min as i128)
+        } else {
+            (min as i128 - start, max as i128 - start)
+        }
+    };
+
+    let (major_delta, minor_delta) = (major_d.unsigned_abs(), minor_d.unsigned_abs());
+    let (signs, steps) = ((major_d.signum(), minor_d.signum()), major_delta);
+    let (low, high) = offsets(major, signs.0, major_min, major_max);
+    if high < 0 || low > steps as i128 {
+        return None;
+    }
+    let mut first = low.max(0) as u128;
+    let mut last = (high as u128).min(steps);
+
+    // Offsets along the minor axis grow monotonously with each step, so the
+    // steps within the bounds are found by inverting the rounded division.
+    let (low, high) = offsets(minor, signs.1, minor_min, minor_max);
+    if high < 0 || low > minor_delta as i128 {
+        return None;
+    }
+    if minor_delta > 0 {
+        let half = major_delta / 2;
+        let low = low.max(0) as u128;
+        if low > 0 {
+            // offset(step) >= low <=> step * minor_delta >= low * major_delta - half
+            let bound = low * major_delta - half;
+            first = first.max(bound.div_ceil(minor_delta));
+        }
+        let high = high as u128;
+        if high < minor_delta {
+            // offset(step) <= high <=> step * minor_delta < (high + 1) * major_delta - half
+            let bound = (high + 1) * major_delta - half - 1;
+            last = last.min(bound / minor_delta);
+        }
+    }
+    if first > last {
+        return None;
+    }
+
+    Some(LineSteps {
+        x_major,
+        start: (major, minor),
+        signs,
+        major_delta: major_delta.max(1),
+        minor_delta,
+        first,
+        last,
+    })
+}
+
+/// Pixels of a line between two pixels (inclusive), which lie within the
+/// given bounds (See [clip_line]).
+pub(crate) fn line_points(
+    (x0, y0): (i64, i64),
+    (x1, y1): (i64, i64),
+    bounds: (i64, i64, i64, i64),
+) -> Vec<(i64, i64)> {
+    clip_line((x0, y0), (x1, y1), bounds)
+        .map(|line| line.points())
+        .unwrap_or_default()
+}
+
+/// Pixels of the outline of a closed polygon through the given pixels, which
+/// lie within the given bounds.
+pub(crate) fn polygon_points(
+    vertices: &[(i64, i64)],
+    bounds: (i64, i64, i64, i64),
+) -> Vec<(i64, i64)> {
+    match vertices {
+        [] => vec![],
+        [single] => line_points(*single, *single, bounds),
+        _ => vertices
+            .iter()
+            .zip(vertices.iter().cycle().skip(1))
+            .flat_map(|(&start, &end)| line_points(start, end, bounds))
+            .collect(),
+    }
+}
+
+/// Draw the outline of an axis aligned ellipse, or the filled ellipse, around
+/// the given center pixel.
+///
+/// A pixel is part of the filled ellipse, if it lies within an ellipse with
+/// radii extended by half a pixel. The outline consists of the outermost
+/// pixels of every row, connected to the rows above and below. Only rows
+/// visible on the canvas are rasterized.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn ellipse<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    cx: i64,
+    cy: i64,
+    rx: u32,
+    ry: u32,
+    filled: bool,
+    color: &Color,
+    blend: BlendMode,
+) {
+    let (rx, ry) = (rx as f64 + 0.5, ry as i64);
+    // Half width of the filled ellipse at the given vertical distance from
+    // the center
+    let half_width = |dy: i64| -> i64 {
+        if dy > ry {
+            return -1;
+        }
+        let ratio = dy as f64 / (ry as f64 + 0.5);
+        (rx * (1.0 - ratio * ratio).sqrt()).floor() as i64
+    };
+
+    let first_row = (-ry).max(0_i64.saturating_sub(cy));
+    let last_row = ry.min((canvas.height() as i64 - 1).saturating_sub(cy));
+    for dy in first_row..=last_row {
+        let (y, width) = (cy + dy, half_width(dy.abs()));
+        let inner = (half_width(dy.abs() + 1) + 1).min(width);
+        let (left, right) = (cx.saturating_sub(width), cx.saturating_add(width));
+        if filled || inner == 0 {
+            span(canvas, left, right, y, color, blend);
+        } else {
+            span(canvas, left, cx.saturating_sub(inner), y, color, blend);
+            span(canvas, cx.saturating_add(inner), right, y, color, blend);
+        }
+    }
+}
+
+/// Fill a polygon using the even-odd rule.
+///
+/// Vertices are given in continuous coordinates, where a pixel `(x, y)`
+/// covers the area from `(x, y)` to `(x + 1, y + 1)`. A pixel is filled, if
+/// its center lies within the polygon. Concave and self-intersecting polygons
+/// are supported.
+pub(crate) fn fill_polygon<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    vertices: &[(f64, f64)],
+    color: &Color,
+    blend: BlendMode,
+) {
+    if vertices.len() < 3 {
+        return;
+    }
+
+    let min_y = vertices.iter().map(|v| v.1).fold(f64::INFINITY, f64::min);
+    let max_y = vertices
+        .iter()
+        .map(|v| v.1)
+        .fold(f64::NEG_INFINITY, f64::max);
+    // Only rows visible on the canvas need to be scanned.
+    let first_row = (min_y - 0.5).ceil().max(0.0) as i64;
+    let last_row = ((max_y - 0.5).ceil() as i64 - 1).min(canvas.height() as i64 - 1);
+
+    let edges: Vec<_> = vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .filter(|(a, b)| a.1 != b.1)
+        .collect();
+    let mut crossings = vec![];
+    for y in first_row..=last_row {
+        let center_y = y as f64 + 0.5;
+        crossings.clear();
+        for (a, b) in &edges {
+            // Half open, so vertices shared by two edges are counted once.
+            if (a.1 <= center_y) != (b.1 <= center_y) {
+                crossings.push(a.0 + (center_y - a.1) * (b.0 - a.0) / (b.1 - a.1));
+            }
+        }
+        crossings.sort_unstable_by(f64::total_cmp);
+
+        for pair in crossings.chunks_exact(2) {
+            // Pixels, whose centers lie between both crossings
+            let x0 = (pair[0] - 0.5).ceil() as i64;
+            let x1 = (pair[1] - 0.5).ceil() as i64 - 1;
+            if x0 <= x1 {
+                span(canvas, x0, x1, y, color, blend);
+            }
+        }
+    }
+}
+
+/// Draw a line with the given thickness between the centers of two pixels.
+///
+/// The line is clipped to the canvas, extended by its thickness, before it is
+/// rasterized.
+pub(crate) fn thick_line<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    (x0, y0): (i64, i64),
+    (x1, y1): (i64, i64),
+    thickness: u32,
+    color: &Color,
+    blend: BlendMode,
+) {
+    if thickness <= 1 {
+        let bounds = canvas_bounds(canvas);
+        return points(
+            canvas,
+            line_points((x0, y0), (x1, y1), bounds),
+            color,
+            blend,
+        );
+    }
+
+    // The direction is taken from the whole line, as the end pixels of the
+    // clipped line only approximate it.
+    let (dx, dy) = (
+        (x1 as i128 - x0 as i128) as f64,
+        (y1 as i128 - y0 as i128) as f64,
+    );
+    let margin = thickness as i64 / 2 + 2;
+    let (_, _, max_x, max_y) = canvas_bounds(canvas);
+    let bounds = (-margin, -margin, max_x + margin, max_y + margin);
+    let Some(clipped) = clip_line((x0, y0), (x1, y1), bounds) else {
+        return;
+    };
+    let ((x0, y0), (x1, y1)) = (clipped.point(clipped.first), clipped.point(clipped.last));
+
+    let length = (dx * dx + dy * dy).sqrt();
+    // Unit vector along the line, which degenerates to a square for a line of
+    // zero length.
+    let (ux, uy) = if length == 0.0 {
+        (1.0, 0.0)
+    } else {
+        (dx / length, dy / length)
+    };
+    let half = thickness as f64 / 2.0;
+    // Normal of the line scaled to half the thickness, and extension at both
+    // ends to cover the end pixels fully.
+    let (nx, ny) = (-uy * half, ux * half);
+    let extension = if length == 0.0 { half } else { 0.5 };
+    let (ex, ey) = (ux * extension, uy * extension);
+    let (cx0, cy0) = (x0 as f64 + 0.5 - ex, y0 as f64 + 0.5 - ey);
+    let (cx1, cy1) = (x1 as f64 + 0.5 + ex, y1 as f64 + 0.5 + ey);
+
+    fill_polygon(
+        canvas,
+        &[
+            (cx0 + nx, cy0 + ny),
+            (cx1 + nx, cy1 + ny),
+            (cx1 - nx, cy1 - ny),
+            (cx0 - nx, cy0 - ny),
+        ],
+        color,
+        blend,
+    );
+}
+
+/// Fill the area of equally colored pixels connected to the given pixel.
+pub(crate) fn flood_fill<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    x: i64,
+    y: i64,
+    color: &Color,
+    blend: BlendMode,
+) {
+    let Some(target) = canvas.maybe_get(x, y).copied() else {
+        return;
+    };
+    let (width, height) = (canvas.width() as i64, canvas.height() as i64);
+    let index = |x: i64, y: i64| (y * width + x) as usize;
+
+    // Find all spans of the area first, as blended colors might match the
+    // target color again.
+    let mut visited = vec![false; (width * height) as usize];
+    let mut spans = vec![];
+    let mut seeds = vec![(x, y)];
+    while let Some((x, y)) = seeds.pop() {
+        if visited[index(x, y)] || canvas.get(x as u32, y as u32) != &target {
+            continue;
+        }
+
+        let matches = |x: i64, visited: &[bool]| {
+            x >= 0
+                && x < width
+                && !visited[index(x, y)]
+                && canvas.get(x as u32, y as u32) == &target
+        };
+        let mut x0 = x;
+        while matches(x0 - 1, &visited) {
+            x0 -= 1;
+        }
+        let mut x1 = x;
+        while matches(x1 + 1, &visited) {
+            x1 += 1;
+        }
+        for x in x0..=x1 {
+            visited[index(x, y)] = true;
+        }
+        spans.push((x0, x1, y));
+
+        // Seed every run of matching pixels above and below the span.
+        for row in [y - 1, y + 1] {
+            if row < 0 || row >= height {
+                continue;
+            }
+            let mut in_run = false;
+            for x in x0..=x1 {
+                let candidate =
+                    !visited[index(x, row)] && canvas.get(x as u32, row as u32) == &target;
+                if candidate && !in_run {
+                    seeds.push((x, row));
+                }
+                in_run = candidate;
+            }
+        }
+    }
+
+    for (x0, x1, y) in spans {
+        span(canvas, x0, x1, y, color, blend);
+    }
+}
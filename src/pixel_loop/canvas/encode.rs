@@ -0,0 +1,40 @@
+//! Encoders writing the contents of a canvas to image files.
+//!
+//! These are used by the default implementations of
+//! [Canvas::write_ppm](super::Canvas::write_ppm) and
+//! [Canvas::write_png](super::Canvas::write_png).
+
+use super::Canvas;
+use anyhow::Result;
+use std::io::Write;
+
+/// Writes the canvas as binary PPM (P6) image.
+///
+/// PPM does not support transparency, therefore the alpha channel is dropped.
+pub(crate) fn write_ppm<C: Canvas + ?Sized, W: Write>(canvas: &C, mut writer: W) -> Result<()> {
+    let (width, height) = (canvas.width(), canvas.height());
+    write!(writer, "P6\n{width} {height}\n255\n")?;
+
+    let pixels = canvas.get_range(0..(width * height) as usize);
+    let data: Vec<u8> = pixels.iter().flat_map(|c| [c.r, c.g, c.b]).collect();
+    writer.write_all(&data)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes the canvas as 8-bit RGBA PNG image.
+#[cfg(feature = "png")]
+pub(crate) fn write_png<C: Canvas + ?Sized, W: Write>(canvas: &C, writer: W) -> Result<()> {
+    use crate::color::ColorAsByteSlice;
+
+    let (width, height) = (canvas.width(), canvas.height());
+    let mut encoder = png::Encoder::new(writer, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let pixels = canvas.get_range(0..(width * height) as usize);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(pixels.as_byte_slice())?;
+    writer.finish()?;
+    Ok(())
+}
@@ -0,0 +1,327 @@
+//! Headless canvas implementation for deterministic execution.
+//!
+//! This module provides a canvas implementation, which does not render to any
+//! real output target. Instead it drives the pixel loop for a fixed number of
+//! frames using a virtual clock and collects the rendered frames in memory.
+//!
+//! It is intended to exercise update and render functions within tests or CI,
+//! where neither a terminal nor a window is available.
+
+use super::{Canvas, InMemoryCanvas, RenderableCanvas};
+use crate::clock::MockTimeSource;
+use crate::color::Color;
+use crate::input::{HeadlessInputState, InputState};
+use crate::recorder::Recorder;
+use crate::{Game, NextLoopState, PixelLoop};
+use anyhow::Result;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::time::Duration;
+
+/// A canvas implementation that renders into memory only.
+///
+/// Every frame is advanced by a fixed virtual frame time, regardless of how
+/// long the update and render functions actually took. This makes the results
+/// of a run fully deterministic.
+///
+/// By default the canvas is driven by a scripted [HeadlessInputState]. Any
+/// other [InputState] implementation may be used instead (See
+/// [with_input_type](HeadlessCanvas::with_input_type)).
+///
+/// # Example
+/// ```
+/// use pixel_loop::canvas::{Canvas, HeadlessCanvas, RenderableCanvas};
+/// use pixel_loop::color::Color;
+/// use pixel_loop::input::{HeadlessInputState, KeyboardKey, KeyboardState};
+/// use pixel_loop::NextLoopState;
+///
+/// let canvas = HeadlessCanvas::new(16, 16).with_frames(10);
+/// let input = HeadlessInputState::new().with_key_press(0, KeyboardKey::Space);
+///
+/// let result = pixel_loop::run_headless(
+///     60,
+///     0u32,
+///     input,
+///     canvas,
+///     |_, s, input, _| {
+///         if input.is_key_down(KeyboardKey::Space) {
+///             *s += 1;
+///         }
+///         Ok(NextLoopState::Continue)
+///     },
+///     |_, _, _, canvas, _| {
+///         canvas.clear_screen(&Color::from_rgb(255, 0, 0));
+///         canvas.render()?;
+///         Ok(NextLoopState::Continue)
+///     },
+/// )?;
+///
+/// assert!(result.state > 0);
+/// assert_eq!(result.exit_code, None);
+/// assert_eq!(result.canvas.get(0, 0), &Color::from_rgb(255, 0, 0));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct HeadlessCanvas<I = HeadlessInputState> {
+    /// The in memory buffer rendered to
+    canvas: InMemoryCanvas,
+    /// Number of frames to run before the loop is finished
+    frames: usize,
+    /// Virtual time advanced with every frame
+    frame_time: Duration,
+    /// Whether a copy of the canvas is taken on every call to render
+    capture_snapshots: bool,
+    /// Copies of the canvas taken on every call to render
+    snapshots: Vec<InMemoryCanvas>,
+    /// The input implementation this canvas is driven with
+    input: PhantomData<I>,
+}
+
+/// The outcome of a headless run of a pixel loop.
+pub struct HeadlessResult<State> {
+    /// The final game state
+    pub state: State,
+    /// The exit code, if the loop was exited before all frames were run
+    pub exit_code: Option<i32>,
+    /// The number of frames, which have been run
+    pub frames: usize,
+    /// The contents of the canvas after the last frame
+    pub canvas: InMemoryCanvas,
+    /// A copy of the canvas for every call to render, if enabled using
+    /// [with_snapshots](HeadlessCanvas::with_snapshots)
+    pub snapshots: Vec<InMemoryCanvas>,
+    /// The recorder attached to the loop, if any (See
+    /// [with_recorder](PixelLoop::with_recorder))
+    pub recorder: Option<Recorder>,
+}
+
+impl<State> HeadlessResult<State> {
+    /// Transforms the final state of this result using the given function.
+    pub fn map_state<T, F: FnOnce(State) -> T>(self, f: F) -> HeadlessResult<T> {
+        HeadlessResult {
+            state: f(self.state),
+            exit_code: self.exit_code,
+            frames: self.frames,
+            canvas: self.canvas,
+            snapshots: self.snapshots,
+            recorder: self.recorder,
+        }
+    }
+}
+
+impl HeadlessCanvas {
+    /// Creates a new headless canvas with the specified dimensions.
+    ///
+    /// By default 60 frames are run, each advancing the virtual clock by 1/60th
+    /// of a second.
+    ///
+    /// # Arguments
+    /// * `width` - The width of the canvas in pixels
+    /// * `height` - The height of the canvas in pixels
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            canvas: InMemoryCanvas::new(width, height, &Color::from_rgb(0, 0, 0)),
+            frames: 60,
+            frame_time: Duration::from_nanos((1_000_000_000f64 / 60f64).round() as u64),
+            capture_snapshots: false,
+            snapshots: vec![],
+            input: PhantomData,
+        }
+    }
+}
+
+impl<I: InputState> HeadlessCanvas<I> {
+    /// Changes the input implementation this canvas is driven with.
+    ///
+    /// # Example
+    /// ```
+    /// use pixel_loop::canvas::HeadlessCanvas;
+    /// use pixel_loop::input::ReplayInputState;
+    ///
+    /// let canvas = HeadlessCanvas::new(16, 16).with_input_type::<ReplayInputState>();
+    /// ```
+    pub fn with_input_type<J: InputState>(self) -> HeadlessCanvas<J> {
+        HeadlessCanvas {
+            canvas: self.canvas,
+            frames: self.frames,
+            frame_time: self.frame_time,
+            capture_snapshots: self.capture_snapshots,
+            snapshots: self.snapshots,
+            input: PhantomData,
+        }
+    }
+
+    /// Sets the number of frames to run before the loop is finished.
+    pub fn with_frames(mut self, frames: usize) -> Self {
+        self.frames = frames;
+        self
+    }
+
+    /// Sets the virtual time advanced with every frame.
+    pub fn with_frame_time(mut self, frame_time: Duration) -> Self {
+        self.frame_time = frame_time;
+        self
+    }
+
+    /// Enables or disables taking a copy of the canvas on every call to
+    /// render.
+    pub fn with_snapshots(mut self, capture_snapshots: bool) -> Self {
+        self.capture_snapshots = capture_snapshots;
+        self
+    }
+
+    /// Runs the given pixel loop for the configured number of frames and
+    /// returns its outcome, instead of exiting the process.
+    ///
+    /// The loop is finished early, if either the input, the update or the
+    /// render function requests an exit. Afterwards it is finished like any
+    /// other loop (See [PixelLoop::finish]), using the exit code `0` if no exit
+    /// has been requested.
+    ///
+    /// # Example
+    /// ```
+    /// use pixel_loop::canvas::{HeadlessCanvas, RenderableCanvas};
+    /// use pixel_loop::input::HeadlessInputState;
+    /// use pixel_loop::{EngineEnvironment, FrameInfo, Game, NextLoopState, PixelLoop, ERROR_EXIT_CODE};
+    /// use anyhow::{bail, Result};
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    ///
+    /// #[derive(Default)]
+    /// struct Session {
+    ///     fail: bool,
+    ///     exited_with: Rc<Cell<Option<i32>>>,
+    /// }
+    ///
+    /// impl<C: RenderableCanvas> Game<C> for Session {
+    ///     fn update(&mut self, _env: &mut EngineEnvironment, _input: &C::Input, _canvas: &mut C) -> Result<NextLoopState> {
+    ///         if self.fail {
+    ///             bail!("update failed");
+    ///         }
+    ///         Ok(NextLoopState::Continue)
+    ///     }
+    ///
+    ///     fn render(&mut self, _env: &mut EngineEnvironment, _input: &C::Input, _canvas: &mut C, _frame: FrameInfo) -> Result<NextLoopState> {
+    ///         Ok(NextLoopState::Continue)
+    ///     }
+    ///
+    ///     fn on_exit(&mut self, _env: &mut EngineEnvironment, code: i32) -> Result<()> {
+    ///         self.exited_with.set(Some(code));
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let result = HeadlessCanvas::run_frames(PixelLoop::from_game(
+    ///     60,
+    ///     Session::default(),
+    ///     HeadlessInputState::new(),
+    ///     HeadlessCanvas::new(8, 8).with_frames(3),
+    /// ))?;
+    ///
+    /// assert_eq!(result.frames, 3);
+    /// assert_eq!(result.state.exited_with.get(), Some(0));
+    ///
+    /// // The loop is finished as well, if it is aborted due to an error
+    /// let exited_with = Rc::new(Cell::new(None));
+    /// let session = Session { fail: true, exited_with: exited_with.clone() };
+    /// let result = HeadlessCanvas::run_frames(PixelLoop::from_game(
+    ///     60,
+    ///     session,
+    ///     HeadlessInputState::new(),
+    ///     HeadlessCanvas::new(8, 8).with_frames(3),
+    /// ));
+    ///
+    /// assert!(result.is_err());
+    /// assert_eq!(exited_with.get(), Some(ERROR_EXIT_CODE));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn run_frames<G: Game<Self>>(
+        mut pixel_loop: PixelLoop<G, Self>,
+    ) -> Result<HeadlessResult<G>> {
+        // Drive the clock of the loop virtually, while keeping any other
+        // configuration like time scaling in place.
+        let time = MockTimeSource::new();
+        pixel_loop.engine_state.clock.set_time_source(time.clone());
+
+        if let Err(error) = pixel_loop.begin() {
+            return Err(pixel_loop.abort(error));
+        }
+
+        let frame_time = pixel_loop.canvas.frame_time;
+        let mut exit_code = None;
+        let mut frames = 0;
+        while frames < pixel_loop.canvas.frames {
+            frames += 1;
+            time.advance(frame_time);
+            match pixel_loop.next_loop() {
+                Err(error) => return Err(pixel_loop.abort(error)),
+                Ok(NextLoopState::Exit(code)) => {
+                    exit_code = Some(code);
+                    break;
+                }
+                Ok(NextLoopState::Continue) => {}
+            }
+        }
+
+        pixel_loop.finish(exit_code.unwrap_or(0))?;
+
+        let PixelLoop {
+            game,
+            canvas,
+            recorder,
+            ..
+        } = pixel_loop;
+        Ok(HeadlessResult {
+            state: game,
+            exit_code,
+            frames,
+            canvas: canvas.canvas,
+            snapshots: canvas.snapshots,
+            recorder,
+        })
+    }
+}
+
+impl<I: InputState> Canvas for HeadlessCanvas<I> {
+    fn width(&self) -> u32 {
+        self.canvas.width()
+    }
+
+    fn height(&self) -> u32 {
+        self.canvas.height()
+    }
+
+    fn set_range(&mut self, range: Range<usize>, color: &[Color]) {
+        self.canvas.set_range(range, color);
+    }
+
+    fn get_range(&self, range: Range<usize>) -> &[Color] {
+        self.canvas.get_range(range)
+    }
+}
+
+impl<I: InputState> RenderableCanvas for HeadlessCanvas<I> {
+    type Input = I;
+
+    fn render(&mut self) -> Result<()> {
+        if self.capture_snapshots {
+            self.snapshots.push(self.canvas.clone());
+        }
+        Ok(())
+    }
+
+    fn resize_surface(&mut self, width: u32, height: u32, _scale_factor: Option<f64>) {
+        self.canvas = InMemoryCanvas::new(width, height, &Color::from_rgb(0, 0, 0));
+    }
+
+    /// Runs the pixel loop for the configured number of frames.
+    ///
+    /// If the loop has not been exited before, the exit code is `0`. Use
+    /// [run_frames](HeadlessCanvas::run_frames) to retrieve the rendered
+    /// frames as well.
+    fn run_to_completion<G: Game<Self> + 'static>(
+        pixel_loop: PixelLoop<G, Self>,
+    ) -> Result<(i32, G)> {
+        let result = Self::run_frames(pixel_loop)?;
+        Ok((result.exit_code.unwrap_or(0), result.state))
+    }
+}
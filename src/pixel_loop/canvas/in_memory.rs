@@ -10,14 +10,64 @@ use crate::color::Color;
 use std::ops::Range;
 
 #[cfg(feature = "stb-image")]
-use anyhow::anyhow;
+use std::fmt;
 #[cfg(feature = "stb-image")]
-use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// Errors occurring while loading an image into an [InMemoryCanvas].
+///
+/// Only available if the `stb-image` feature is enabled.
+#[cfg(feature = "stb-image")]
+#[derive(Debug)]
+pub enum ImageLoadError {
+    /// The image file could not be read
+    Io {
+        /// Path of the image file
+        path: PathBuf,
+        /// The underlying error
+        source: std::io::Error,
+    },
+    /// The image data is invalid, corrupted or in an unsupported format. The
+    /// reason reported by the decoder is attached.
+    Decode(String),
+    /// The image is an HDR (32-bit float) image
+    Hdr,
+    /// The image has an unsupported number of channels
+    UnsupportedChannels(usize),
+}
+
+#[cfg(feature = "stb-image")]
+impl fmt::Display for ImageLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageLoadError::Io { path, .. } => {
+                write!(f, "Could not read image file {}", path.display())
+            }
+            ImageLoadError::Decode(msg) => write!(f, "Could not decode image: {msg}"),
+            ImageLoadError::Hdr => write!(f, "Could not load hdr image"),
+            ImageLoadError::UnsupportedChannels(channels) => write!(
+                f,
+                "Could not load image with {channels} channels. Only 1 to 4 are supported"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "stb-image")]
+impl std::error::Error for ImageLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ImageLoadError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
 
 /// A canvas implementation that stores pixel data in memory.
 ///
 /// This canvas provides basic pixel manipulation operations and can be used
 /// to load and manipulate images in memory.
+#[derive(Clone)]
 pub struct InMemoryCanvas {
     /// The pixel buffer storing all colors
     buffer: Vec<Color>,
@@ -55,6 +105,11 @@ impl InMemoryCanvas {
     /// [stb_image](https://github.com/nothings/stb/blob/master/stb_image.h)
     /// library.
     ///
+    /// Grayscale, grayscale with alpha, RGB and RGBA images are supported.
+    /// Palette based images are expanded to RGB(A) while decoding. Grayscale
+    /// values are copied to all color channels, images without an alpha
+    /// channel are fully opaque.
+    ///
     /// Only available if the `stb-image` feature is enabled.
     ///
     /// # Arguments
@@ -66,40 +121,50 @@ impl InMemoryCanvas {
     ///
     /// # Errors
     /// Returns an error if:
-    /// * The image data is invalid or corrupted
-    /// * The image is HDR (32-bit float)
-    /// * The image depth is not 3 (RGB)
+    /// * The image data is invalid or corrupted ([ImageLoadError::Decode])
+    /// * The image is HDR (32-bit float) ([ImageLoadError::Hdr])
+    /// * The image has more than 4 channels
+    ///   ([ImageLoadError::UnsupportedChannels])
     ///
     /// # Examples
     /// ```
-    /// use pixel_loop::canvas::InMemoryCanvas;
+    /// use pixel_loop::canvas::{Canvas, InMemoryCanvas};
+    /// use pixel_loop::canvas::in_memory::ImageLoadError;
+    /// use pixel_loop::color::Color;
+    ///
+    /// // A 2x1 grayscale image in the binary PGM format
+    /// let image_bytes = b"P5 2 1 255 \x00\x80";
+    /// let canvas = InMemoryCanvas::from_in_memory_image(image_bytes)?;
+    /// assert_eq!(canvas.get(1, 0), &Color::from_rgb(128, 128, 128));
     ///
-    /// let image_bytes = std::fs::read("example.jpg").unwrap();
-    /// let canvas = InMemoryCanvas::from_in_memory_image(&image_bytes)?;
+    /// assert!(matches!(
+    ///     InMemoryCanvas::from_in_memory_image(b"no image"),
+    ///     Err(ImageLoadError::Decode(_))
+    /// ));
+    /// # Ok::<(), ImageLoadError>(())
     /// ```
     #[cfg(feature = "stb-image")]
-    pub fn from_in_memory_image(bytes: &[u8]) -> Result<Self> {
+    pub fn from_in_memory_image(bytes: &[u8]) -> Result<Self, ImageLoadError> {
         use stb_image::image;
         use stb_image::image::LoadResult::*;
         match image::load_from_memory(bytes) {
-            Error(msg) => Err(anyhow!("Could not load image from memory: {msg}")),
-            ImageF32(_) => Err(anyhow!("Could not load hdr image from memory")),
+            Error(msg) => Err(ImageLoadError::Decode(msg)),
+            ImageF32(_) => Err(ImageLoadError::Hdr),
             ImageU8(image) => {
-                if image.depth != 3 {
-                    return Err(anyhow!(
-                        "Could not load image with depth != 3. It has {depth}",
-                        depth = image.depth
-                    ));
-                }
+                let to_color: fn(&[u8]) -> Color = match image.depth {
+                    1 => |p| Color::from_rgb(p[0], p[0], p[0]),
+                    2 => |p| Color::from_rgba(p[0], p[0], p[0], p[1]),
+                    3 => |p| Color::from_rgb(p[0], p[1], p[2]),
+                    4 => |p| Color::from_rgba(p[0], p[1], p[2], p[3]),
+                    depth => return Err(ImageLoadError::UnsupportedChannels(depth)),
+                };
 
-                let mut buffer: Vec<Color> = Vec::with_capacity(image.width * image.height);
-                for i in (0..image.width * image.height * image.depth).step_by(image.depth) {
-                    buffer.push(Color::from_rgb(
-                        image.data[i],
-                        image.data[i + 1],
-                        image.data[i + 2],
-                    ))
-                }
+                let buffer: Vec<Color> = image
+                    .data
+                    .chunks_exact(image.depth)
+                    .take(image.width * image.height)
+                    .map(to_color)
+                    .collect();
 
                 Ok(Self {
                     width: image.width as u32,
@@ -109,6 +174,35 @@ impl InMemoryCanvas {
             }
         }
     }
+
+    /// Creates a new canvas by loading an image file from disk.
+    ///
+    /// Supports the same formats as
+    /// [from_in_memory_image](InMemoryCanvas::from_in_memory_image).
+    ///
+    /// Only available if the `stb-image` feature is enabled.
+    ///
+    /// # Errors
+    /// Returns [ImageLoadError::Io] if the file could not be read, and the
+    /// errors of [from_in_memory_image](InMemoryCanvas::from_in_memory_image)
+    /// otherwise.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use pixel_loop::canvas::InMemoryCanvas;
+    ///
+    /// let canvas = InMemoryCanvas::from_file("assets/sprites.png")?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    #[cfg(feature = "stb-image")]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ImageLoadError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|source| ImageLoadError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_in_memory_image(&bytes)
+    }
 }
 
 impl Canvas for InMemoryCanvas {
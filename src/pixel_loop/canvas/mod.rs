@@ -5,7 +5,15 @@
 //! of pixels from one canvas to another.
 //!
 //! Furthermore it provides convenience methods for drawing certain shape
-//! primitives (eg. rectangles).
+//! primitives (eg. rectangles, lines, circles and polygons).
+//!
+//! All drawing operations exist in a variant taking a [BlendMode], which
+//! defines how drawn colors are combined with the ones already present on the
+//! canvas (eg. to respect their alpha channel). The plain variants replace the
+//! present colors.
+//!
+//! The contents of any canvas can be saved as PPM image, or as PNG image if the
+//! `png` feature is enabled.
 //!
 //! It is the goto abstraction for rendering pixels in the pixel_loop library.
 
@@ -14,6 +22,12 @@ pub mod crossterm;
 #[cfg(feature = "crossterm")]
 pub use crossterm::CrosstermCanvas;
 
+mod draw;
+mod encode;
+
+pub mod headless;
+pub use headless::HeadlessCanvas;
+
 pub mod in_memory;
 pub use in_memory::InMemoryCanvas;
 
@@ -22,12 +36,15 @@ pub mod pixels;
 #[cfg(feature = "pixels")]
 pub use pixels::PixelsCanvas;
 
-use crate::color::Color;
+use crate::color::{BlendMode, Color};
 use crate::input::InputState;
-use crate::PixelLoop;
+use crate::{Game, PixelLoop};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{BufWriter, Write};
 use std::ops::Range;
+use std::path::Path;
 
 /// Trait representing a basic canvas that can be drawn to.
 ///
@@ -48,6 +65,23 @@ pub trait Canvas {
     /// Get a range of pixels as a slice of [Color]s
     fn get_range(&self, range: Range<usize>) -> &[Color];
 
+    /// Draw a range of pixels, combining them with the present ones using the
+    /// given [BlendMode].
+    fn set_range_blended(&mut self, range: Range<usize>, colors: &[Color], blend: BlendMode) {
+        if blend == BlendMode::Replace {
+            self.set_range(range, colors);
+            return;
+        }
+
+        let blended: Vec<Color> = self
+            .get_range(range.clone())
+            .iter()
+            .zip(colors)
+            .map(|(dst, src)| blend.blend(*src, *dst))
+            .collect();
+        self.set_range(range, &blended);
+    }
+
     /// Blit a full input canvas to this canvas instance at a given position,
     /// optionally tinting the input canvas with a color.
     ///
@@ -57,7 +91,22 @@ pub trait Canvas {
     /// * `dst_y` - The y position to blit the source canvas to
     /// * `tint` - An optional color to tint the source canvas with
     fn blit<C: Canvas>(&mut self, src_canvas: &C, dst_x: i64, dst_y: i64, tint: Option<&Color>) {
-        self.blit_rect(
+        self.blit_blended(src_canvas, dst_x, dst_y, tint, BlendMode::Replace)
+    }
+
+    /// Blit a full input canvas to this canvas instance at a given position,
+    /// combining its pixels with the present ones using the given [BlendMode].
+    ///
+    /// See also: [blit](crate::canvas::Canvas::blit)
+    fn blit_blended<C: Canvas>(
+        &mut self,
+        src_canvas: &C,
+        dst_x: i64,
+        dst_y: i64,
+        tint: Option<&Color>,
+        blend: BlendMode,
+    ) {
+        self.blit_rect_blended(
             src_canvas,
             0,
             0,
@@ -66,6 +115,7 @@ pub trait Canvas {
             dst_x,
             dst_y,
             tint,
+            blend,
         )
     }
 
@@ -85,32 +135,73 @@ pub trait Canvas {
         dst_x: i64,
         dst_y: i64,
         tint: Option<&Color>,
+    ) {
+        self.blit_rect_blended(
+            src_canvas,
+            src_x,
+            src_y,
+            width,
+            height,
+            dst_x,
+            dst_y,
+            tint,
+            BlendMode::Replace,
+        )
+    }
+
+    /// Blit only a rectangular region of the input canvas to this canvas
+    /// instance at a given position, combining its pixels with the present
+    /// ones using the given [BlendMode].
+    ///
+    /// The tint is applied to all channels of the source pixels (including
+    /// alpha) before they are blended.
+    ///
+    /// See also: [blit_rect](crate::canvas::Canvas::blit_rect)
+    #[allow(clippy::too_many_arguments)]
+    fn blit_rect_blended<C: Canvas>(
+        &mut self,
+        src_canvas: &C,
+        src_x: u32,
+        src_y: u32,
+        width: u32,
+        height: u32,
+        dst_x: i64,
+        dst_y: i64,
+        tint: Option<&Color>,
+        blend: BlendMode,
     ) {
         if let Some((norm_dst_x, norm_dst_y, norm_width, norm_height)) =
             self.clip_rect(dst_x, dst_y, width, height)
         {
+            // Skip the part of the source rectangle, which has been clipped at
+            // the top or left edge.
+            let src_x = src_x + (norm_dst_x as i64 - dst_x) as u32;
+            let src_y = src_y + (norm_dst_y as i64 - dst_y) as u32;
             for y in 0..norm_height {
                 let src_start = (((src_y + y) * src_canvas.width()) + src_x) as usize;
-                let src_end = src_start + u32::min(width, norm_width) as usize;
+                let src_end = src_start + norm_width as usize;
                 let dst_start = (((norm_dst_y + y) * self.width()) + norm_dst_x) as usize;
                 let dst_end = dst_start + norm_width as usize;
                 let row = src_canvas.get_range(src_start..src_end);
 
                 if let Some(tint) = tint {
-                    self.set_range(
+                    let tint_channel = |c: u8, t: u8| (c as usize * t as usize / 255_usize) as u8;
+                    self.set_range_blended(
                         dst_start..dst_end,
                         &row.iter()
                             .map(|c| {
-                                Color::from_rgb(
-                                    (c.r as usize * tint.r as usize / 255_usize) as u8,
-                                    (c.g as usize * tint.g as usize / 255_usize) as u8,
-                                    (c.b as usize * tint.b as usize / 255_usize) as u8,
+                                Color::from_rgba(
+                                    tint_channel(c.r, tint.r),
+                                    tint_channel(c.g, tint.g),
+                                    tint_channel(c.b, tint.b),
+                                    tint_channel(c.a, tint.a),
                                 )
                             })
                             .collect::<Vec<Color>>(),
+                        blend,
                     );
                 } else {
-                    self.set_range(dst_start..dst_end, row);
+                    self.set_range_blended(dst_start..dst_end, row, blend);
                 }
             }
         }
@@ -154,6 +245,13 @@ pub trait Canvas {
         self.set_range(i..i + 1, std::slice::from_ref(color));
     }
 
+    /// Draw a specific pixel at a given position, combining it with the
+    /// present one using the given [BlendMode].
+    fn set_blended(&mut self, x: u32, y: u32, color: &Color, blend: BlendMode) {
+        let i = (y * self.width() + x) as usize;
+        self.set_range_blended(i..i + 1, std::slice::from_ref(color), blend);
+    }
+
     /// Clip a rectangle to the bounds of the canvas.
     ///
     /// # Returns
@@ -162,14 +260,14 @@ pub trait Canvas {
     fn clip_rect(&self, x: i64, y: i64, width: u32, height: u32) -> Option<(u32, u32, u32, u32)> {
         let width = width as i64;
         let height = height as i64;
-        if x < -width || y < -height || x >= self.width() as i64 || y >= self.height() as i64 {
+        if x <= -width || y <= -height || x >= self.width() as i64 || y >= self.height() as i64 {
             // Completely out of view
             None
         } else {
             let norm_x = i64::max(0, x);
             let norm_y = i64::max(0, y);
-            let norm_width = i64::min(width - (norm_x - x), self.width() as i64 - norm_x - 1);
-            let norm_height = i64::min(height - (norm_y - y), self.height() as i64 - norm_y - 1);
+            let norm_width = i64::min(width - (norm_x - x), self.width() as i64 - norm_x);
+            let norm_height = i64::min(height - (norm_y - y), self.height() as i64 - norm_y);
             Some((
                 norm_x as u32,
                 norm_y as u32,
@@ -186,16 +284,467 @@ pub trait Canvas {
 
     /// Draw a filled rectangle at a given position with a given width and height
     fn filled_rect(&mut self, sx: i64, sy: i64, width: u32, height: u32, color: &Color) {
+        self.filled_rect_blended(sx, sy, width, height, color, BlendMode::Replace)
+    }
+
+    /// Draw a filled rectangle at a given position with a given width and
+    /// height, combining it with the present pixels using the given
+    /// [BlendMode].
+    ///
+    /// # Examples
+    /// ```
+    /// use pixel_loop::canvas::{Canvas, InMemoryCanvas};
+    /// use pixel_loop::color::{BlendMode, Color};
+    ///
+    /// let mut canvas = InMemoryCanvas::new(4, 4, &Color::from_rgb(0, 0, 0));
+    /// canvas.filled_rect_blended(
+    ///     0,
+    ///     0,
+    ///     2,
+    ///     2,
+    ///     &Color::from_rgba(255, 255, 255, 0),
+    ///     BlendMode::Alpha,
+    /// );
+    /// assert_eq!(canvas.get(0, 0), &Color::from_rgb(0, 0, 0));
+    /// ```
+    fn filled_rect_blended(
+        &mut self,
+        sx: i64,
+        sy: i64,
+        width: u32,
+        height: u32,
+        color: &Color,
+        blend: BlendMode,
+    ) {
         if let Some((sx, sy, width, height)) = self.clip_rect(sx, sy, width, height) {
             let color_row = vec![*color; width as usize];
             for y in sy..sy + height {
-                self.set_range(
+                self.set_range_blended(
                     (y * self.width() + sx) as usize..(y * self.width() + sx + width) as usize,
                     color_row.as_slice(),
+                    blend,
                 );
             }
         }
     }
+
+    /// Draw a line of one pixel width from one pixel to another (inclusive).
+    ///
+    /// # Examples
+    /// ```
+    /// use pixel_loop::canvas::{Canvas, InMemoryCanvas};
+    /// use pixel_loop::color::Color;
+    ///
+    /// let white = Color::from_rgb(255, 255, 255);
+    /// let mut canvas = InMemoryCanvas::new(4, 4, &Color::from_rgb(0, 0, 0));
+    /// canvas.line(0, 0, 3, 3, &white);
+    /// assert_eq!(canvas.get(2, 2), &white);
+    /// assert_eq!(canvas.get(3, 3), &white);
+    /// ```
+    ///
+    /// Lines are clipped to the canvas before they are rasterized, so even
+    /// huge lines are drawn quickly:
+    /// ```
+    /// use pixel_loop::canvas::{Canvas, InMemoryCanvas};
+    /// use pixel_loop::color::Color;
+    ///
+    /// let black = Color::from_rgb(0, 0, 0);
+    /// let white = Color::from_rgb(255, 255, 255);
+    /// let mut canvas = InMemoryCanvas::new(8, 8, &black);
+    /// canvas.line(-(1 << 62), 1, 1 << 62, 1, &white);
+    /// canvas.line(0, 0, 50_000_000, 3, &white);
+    /// canvas.thick_line(i64::MIN, 5, i64::MAX, 5, 3, &white);
+    /// assert_eq!(canvas.get(7, 1), &white);
+    /// assert_eq!(canvas.get(0, 0), &white);
+    /// assert_eq!(canvas.get(3, 4), &white);
+    /// assert_eq!(canvas.get(3, 6), &white);
+    /// assert_eq!(canvas.get(3, 7), &black);
+    /// ```
+    fn line(&mut self, x0: i64, y0: i64, x1: i64, y1: i64, color: &Color) {
+        self.line_blended(x0, y0, x1, y1, color, BlendMode::Replace)
+    }
+
+    /// Draw a line of one pixel width from one pixel to another (inclusive),
+    /// combining it with the present pixels using the given [BlendMode].
+    fn line_blended(
+        &mut self,
+        x0: i64,
+        y0: i64,
+        x1: i64,
+        y1: i64,
+        color: &Color,
+        blend: BlendMode,
+    ) {
+        let bounds = draw::canvas_bounds(self);
+        let points = draw::line_points((x0, y0), (x1, y1), bounds);
+        draw::points(self, points, color, blend)
+    }
+
+    /// Draw a line of the given thickness from the center of one pixel to the
+    /// center of another one.
+    ///
+    /// The line is extended by half a pixel at both ends, so a thickness of
+    /// `1` covers the same length as [line](Canvas::line).
+    fn thick_line(&mut self, x0: i64, y0: i64, x1: i64, y1: i64, thickness: u32, color: &Color) {
+        self.thick_line_blended(x0, y0, x1, y1, thickness, color, BlendMode::Replace)
+    }
+
+    /// Draw a line of the given thickness from the center of one pixel to the
+    /// center of another one, combining it with the present pixels using the
+    /// given [BlendMode].
+    #[allow(clippy::too_many_arguments)]
+    fn thick_line_blended(
+        &mut self,
+        x0: i64,
+        y0: i64,
+        x1: i64,
+        y1: i64,
+        thickness: u32,
+        color: &Color,
+        blend: BlendMode,
+    ) {
+        draw::thick_line(self, (x0, y0), (x1, y1), thickness, color, blend)
+    }
+
+    /// Draw the one pixel wide outline of a rectangle at a given position with
+    /// a given width and height.
+    ///
+    /// The outline covers the same pixels as the border of
+    /// [filled_rect](Canvas::filled_rect).
+    fn rect(&mut self, x: i64, y: i64, width: u32, height: u32, color: &Color) {
+        self.rect_blended(x, y, width, height, color, BlendMode::Replace)
+    }
+
+    /// Draw the one pixel wide outline of a rectangle, combining it with the
+    /// present pixels using the given [BlendMode].
+    fn rect_blended(
+        &mut self,
+        x: i64,
+        y: i64,
+        width: u32,
+        height: u32,
+        color: &Color,
+        blend: BlendMode,
+    ) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        let right = x.saturating_add(width as i64 - 1);
+        let bottom = y.saturating_add(height as i64 - 1);
+        draw::span(self, x, right, y, color, blend);
+        if bottom > y {
+            draw::span(self, x, right, bottom, color, blend);
+        }
+        // Only rows visible on the canvas need to be drawn.
+        let rows = y.saturating_add(1).max(0)..bottom.min(self.height() as i64);
+        for row in rows {
+            draw::span(self, x, x, row, color, blend);
+            if right > x {
+                draw::span(self, right, right, row, color, blend);
+            }
+        }
+    }
+
+    /// Draw the one pixel wide outline of a circle around the given center
+    /// pixel.
+    fn circle(&mut self, cx: i64, cy: i64, radius: u32, color: &Color) {
+        self.ellipse_blended(cx, cy, radius, radius, color, BlendMode::Replace)
+    }
+
+    /// Draw the one pixel wide outline of a circle, combining it with the
+    /// present pixels using the given [BlendMode].
+    fn circle_blended(&mut self, cx: i64, cy: i64, radius: u32, color: &Color, blend: BlendMode) {
+        self.ellipse_blended(cx, cy, radius, radius, color, blend)
+    }
+
+    /// Draw a filled circle around the given center pixel.
+    ///
+    /// It covers the same pixels as the outline drawn by
+    /// [circle](Canvas::circle) and everything within.
+    ///
+    /// # Examples
+    /// ```
+    /// use pixel_loop::canvas::{Canvas, InMemoryCanvas};
+    /// use pixel_loop::color::Color;
+    ///
+    /// let black = Color::from_rgb(0, 0, 0);
+    /// let white = Color::from_rgb(255, 255, 255);
+    /// let mut canvas = InMemoryCanvas::new(8, 8, &black);
+    /// canvas.filled_circle(3, 3, 3, &white);
+    /// assert_eq!(canvas.get(0, 3), &white);
+    /// assert_eq!(canvas.get(3, 6), &white);
+    /// assert_eq!(canvas.get(0, 0), &black);
+    /// ```
+    ///
+    /// Only rows visible on the canvas are rasterized, so even huge circles
+    /// are drawn quickly:
+    /// ```
+    /// use pixel_loop::canvas::{Canvas, InMemoryCanvas};
+    /// use pixel_loop::color::Color;
+    ///
+    /// let black = Color::from_rgb(0, 0, 0);
+    /// let white = Color::from_rgb(255, 255, 255);
+    /// let mut canvas = InMemoryCanvas::new(8, 8, &black);
+    ///
+    /// // The outline is far outside of the canvas
+    /// canvas.circle(4, 4, 20_000_000, &white);
+    /// assert_eq!(canvas.get(0, 0), &black);
+    ///
+    /// canvas.filled_circle(4, 4, u32::MAX, &white);
+    /// assert_eq!(canvas.get(0, 0), &white);
+    /// assert_eq!(canvas.get(7, 7), &white);
+    /// ```
+    fn filled_circle(&mut self, cx: i64, cy: i64, radius: u32, color: &Color) {
+        self.filled_ellipse_blended(cx, cy, radius, radius, color, BlendMode::Replace)
+    }
+
+    /// Draw a filled circle, combining it with the present pixels using the
+    /// given [BlendMode].
+    fn filled_circle_blended(
+        &mut self,
+        cx: i64,
+        cy: i64,
+        radius: u32,
+        color: &Color,
+        blend: BlendMode,
+    ) {
+        self.filled_ellipse_blended(cx, cy, radius, radius, color, blend)
+    }
+
+    /// Draw the one pixel wide outline of an axis aligned ellipse around the
+    /// given center pixel with the given horizontal and vertical radius.
+    fn ellipse(&mut self, cx: i64, cy: i64, rx: u32, ry: u32, color: &Color) {
+        self.ellipse_blended(cx, cy, rx, ry, color, BlendMode::Replace)
+    }
+
+    /// Draw the one pixel wide outline of an axis aligned ellipse, combining
+    /// it with the present pixels using the given [BlendMode].
+    fn ellipse_blended(
+        &mut self,
+        cx: i64,
+        cy: i64,
+        rx: u32,
+        ry: u32,
+        color: &Color,
+        blend: BlendMode,
+    ) {
+        draw::ellipse(self, cx, cy, rx, ry, false, color, blend)
+    }
+
+    /// Draw a filled axis aligned ellipse around the given center pixel with
+    /// the given horizontal and vertical radius.
+    ///
+    /// It covers the same pixels as the outline drawn by
+    /// [ellipse](Canvas::ellipse) and everything within.
+    fn filled_ellipse(&mut self, cx: i64, cy: i64, rx: u32, ry: u32, color: &Color) {
+        self.filled_ellipse_blended(cx, cy, rx, ry, color, BlendMode::Replace)
+    }
+
+    /// Draw a filled axis aligned ellipse, combining it with the present
+    /// pixels using the given [BlendMode].
+    fn filled_ellipse_blended(
+        &mut self,
+        cx: i64,
+        cy: i64,
+        rx: u32,
+        ry: u32,
+        color: &Color,
+        blend: BlendMode,
+    ) {
+        draw::ellipse(self, cx, cy, rx, ry, true, color, blend)
+    }
+
+    /// Draw the one pixel wide outline of a triangle through the given pixels.
+    fn triangle(&mut self, p0: (i64, i64), p1: (i64, i64), p2: (i64, i64), color: &Color) {
+        self.polygon_blended(&[p0, p1, p2], color, BlendMode::Replace)
+    }
+
+    /// Draw the one pixel wide outline of a triangle, combining it with the
+    /// present pixels using the given [BlendMode].
+    fn triangle_blended(
+        &mut self,
+        p0: (i64, i64),
+        p1: (i64, i64),
+        p2: (i64, i64),
+        color: &Color,
+        blend: BlendMode,
+    ) {
+        self.polygon_blended(&[p0, p1, p2], color, blend)
+    }
+
+    /// Draw a filled triangle with the given corners (See
+    /// [filled_polygon](Canvas::filled_polygon)).
+    fn filled_triangle(&mut self, p0: (i64, i64), p1: (i64, i64), p2: (i64, i64), color: &Color) {
+        self.filled_polygon_blended(&[p0, p1, p2], color, BlendMode::Replace)
+    }
+
+    /// Draw a filled triangle with the given corners, combining it with the
+    /// present pixels using the given [BlendMode].
+    fn filled_triangle_blended(
+        &mut self,
+        p0: (i64, i64),
+        p1: (i64, i64),
+        p2: (i64, i64),
+        color: &Color,
+        blend: BlendMode,
+    ) {
+        self.filled_polygon_blended(&[p0, p1, p2], color, blend)
+    }
+
+    /// Draw the one pixel wide outline of a closed polygon through the given
+    /// pixels.
+    fn polygon(&mut self, vertices: &[(i64, i64)], color: &Color) {
+        self.polygon_blended(vertices, color, BlendMode::Replace)
+    }
+
+    /// Draw the one pixel wide outline of a closed polygon, combining it with
+    /// the present pixels using the given [BlendMode].
+    fn polygon_blended(&mut self, vertices: &[(i64, i64)], color: &Color, blend: BlendMode) {
+        let points = draw::polygon_points(vertices, draw::canvas_bounds(self));
+        draw::points(self, points, color, blend)
+    }
+
+    /// Draw a filled polygon with the given corners.
+    ///
+    /// Convex, concave and self intersecting polygons are filled using the
+    /// even-odd rule. In contrast to the outline functions the corners are
+    /// given in the coordinates of the pixel edges, like the area of
+    /// [filled_rect](Canvas::filled_rect): A pixel is filled if its center
+    /// lies within the polygon. Therefore polygons sharing an edge do not
+    /// overlap.
+    ///
+    /// # Examples
+    /// ```
+    /// use pixel_loop::canvas::{Canvas, InMemoryCanvas};
+    /// use pixel_loop::color::Color;
+    ///
+    /// let black = Color::from_rgb(0, 0, 0);
+    /// let white = Color::from_rgb(255, 255, 255);
+    ///
+    /// // A "U" shape: a 6x6 square with a 2x4 notch at the top
+    /// let mut canvas = InMemoryCanvas::new(6, 6, &black);
+    /// canvas.filled_polygon(
+    ///     &[(0, 0), (2, 0), (2, 4), (4, 4), (4, 0), (6, 0), (6, 6), (0, 6)],
+    ///     &white,
+    /// );
+    /// assert_eq!(canvas.get(1, 1), &white);
+    /// assert_eq!(canvas.get(3, 1), &black);
+    /// assert_eq!(canvas.get(3, 5), &white);
+    /// assert_eq!(canvas.get(5, 5), &white);
+    /// ```
+    fn filled_polygon(&mut self, vertices: &[(i64, i64)], color: &Color) {
+        self.filled_polygon_blended(vertices, color, BlendMode::Replace)
+    }
+
+    /// Draw a filled polygon with the given corners, combining it with the
+    /// present pixels using the given [BlendMode].
+    fn filled_polygon_blended(&mut self, vertices: &[(i64, i64)], color: &Color, blend: BlendMode) {
+        let vertices: Vec<(f64, f64)> = vertices
+            .iter()
+            .map(|&(x, y)| (x as f64, y as f64))
+            .collect();
+        draw::fill_polygon(self, &vertices, color, blend)
+    }
+
+    /// Fill the area of equally colored pixels connected (horizontally or
+    /// vertically) to the given pixel with a color.
+    ///
+    /// Nothing is drawn, if the pixel is out of bounds.
+    ///
+    /// # Examples
+    /// ```
+    /// use pixel_loop::canvas::{Canvas, InMemoryCanvas};
+    /// use pixel_loop::color::Color;
+    ///
+    /// let black = Color::from_rgb(0, 0, 0);
+    /// let white = Color::from_rgb(255, 255, 255);
+    /// let red = Color::from_rgb(255, 0, 0);
+    ///
+    /// let mut canvas = InMemoryCanvas::new(8, 8, &black);
+    /// canvas.rect(2, 2, 4, 4, &white);
+    /// canvas.flood_fill(3, 3, &red);
+    /// assert_eq!(canvas.get(4, 4), &red);
+    /// assert_eq!(canvas.get(2, 2), &white);
+    /// assert_eq!(canvas.get(0, 0), &black);
+    /// ```
+    fn flood_fill(&mut self, x: i64, y: i64, color: &Color) {
+        self.flood_fill_blended(x, y, color, BlendMode::Replace)
+    }
+
+    /// Fill the area of equally colored pixels connected to the given pixel,
+    /// combining it with the present pixels using the given [BlendMode].
+    fn flood_fill_blended(&mut self, x: i64, y: i64, color: &Color, blend: BlendMode) {
+        draw::flood_fill(self, x, y, color, blend)
+    }
+
+    /// Write the contents of the canvas as binary PPM (P6) image.
+    ///
+    /// PPM does not support transparency, therefore the alpha channel is
+    /// dropped. No further dependencies are needed to write PPM images.
+    ///
+    /// # Examples
+    /// ```
+    /// use pixel_loop::canvas::{Canvas, InMemoryCanvas};
+    /// use pixel_loop::color::Color;
+    ///
+    /// let canvas = InMemoryCanvas::new(2, 1, &Color::from_rgb(255, 128, 0));
+    /// let mut ppm = vec![];
+    /// canvas.write_ppm(&mut ppm)?;
+    /// assert_eq!(ppm, b"P6\n2 1\n255\n\xff\x80\x00\xff\x80\x00");
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    fn write_ppm<W: Write>(&self, writer: W) -> Result<()> {
+        encode::write_ppm(self, writer)
+    }
+
+    /// Save the contents of the canvas as binary PPM (P6) image at the given
+    /// path (See [write_ppm](Canvas::write_ppm)).
+    ///
+    /// An already existing file is overwritten.
+    fn save_ppm<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let file =
+            File::create(path).with_context(|| format!("create ppm image {}", path.display()))?;
+        self.write_ppm(BufWriter::new(file))
+            .with_context(|| format!("write ppm image {}", path.display()))
+    }
+
+    /// Write the contents of the canvas as 8-bit RGBA PNG image.
+    ///
+    /// Only available if the `png` feature is enabled.
+    #[cfg(feature = "png")]
+    fn write_png<W: Write>(&self, writer: W) -> Result<()> {
+        encode::write_png(self, writer)
+    }
+
+    /// Save the contents of the canvas as 8-bit RGBA PNG image at the given
+    /// path.
+    ///
+    /// An already existing file is overwritten. Only available if the `png`
+    /// feature is enabled.
+    ///
+    /// # Examples
+    /// ```
+    /// use pixel_loop::canvas::{Canvas, InMemoryCanvas};
+    /// use pixel_loop::color::Color;
+    ///
+    /// let canvas = InMemoryCanvas::new(32, 32, &Color::from_rgba(255, 0, 0, 128));
+    /// let path = std::env::temp_dir().join("pixel_loop_save_png_example.png");
+    /// canvas.save_png(&path)?;
+    ///
+    /// let loaded = InMemoryCanvas::from_file(&path)?;
+    /// assert_eq!(loaded.get(16, 16), &Color::from_rgba(255, 0, 0, 128));
+    /// # std::fs::remove_file(&path)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    #[cfg(feature = "png")]
+    fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let file =
+            File::create(path).with_context(|| format!("create png image {}", path.display()))?;
+        self.write_png(BufWriter::new(file))
+            .with_context(|| format!("write png image {}", path.display()))
+    }
 }
 
 /// Trait representing a canvas that can be rendered to a display target, like a
@@ -222,29 +771,60 @@ pub trait RenderableCanvas: Canvas {
     /// rendering setup of the target system.
     fn resize_surface(&mut self, width: u32, height: u32, scale_factor: Option<f64>);
 
-    // @TODO: Could this be generally handled/state set somehow within next_loop?
-    /// Can be asked in an update loop, to check if the canvas resized since the last update loop.
-    ///
-    /// If it resized the new size is returned as a tuple. Otherwise None is returned.
-    fn did_resize(&self) -> Option<(u32, u32)>;
-
-    /// Main run loop for a pixel loop that renders to this canvas.
+    /// Main run loop for a pixel loop that renders to this canvas, which
+    /// returns once the loop has been exited.
     ///
     /// A fully instantiated and configured pixel loop instance is provided to this method.
+    /// The exit code as well as the final game are returned.
     ///
     /// A minimal implementation of this method would look like this:
-    /// ```rust
+    /// ```ignore
     /// {
-    ///    pixel_loop.begin().expect("begin pixel_loop");
+    ///    if let Err(error) = pixel_loop.begin() {
+    ///        return Err(pixel_loop.abort(error));
+    ///    }
     ///    loop {
-    ///        pixel_loop.next_loop().expect("next_loop pixel_loop");
+    ///        match pixel_loop.next_loop() {
+    ///            Err(error) => return Err(pixel_loop.abort(error)),
+    ///            Ok(NextLoopState::Exit(code)) => {
+    ///                pixel_loop.finish(code)?;
+    ///                return Ok((code, pixel_loop.into_game()));
+    ///            }
+    ///            Ok(NextLoopState::Continue) => {}
+    ///        }
     ///    }
     /// }
     /// ```
-    fn run<State: 'static>(pixel_loop: PixelLoop<State, Self>) -> !
+    fn run_to_completion<G: Game<Self> + 'static>(
+        pixel_loop: PixelLoop<G, Self>,
+    ) -> Result<(i32, G)>
     where
         Self: Sized;
 
+    /// Main run loop for a pixel loop that renders to this canvas.
+    ///
+    /// The process is exited with the exit code of the loop, once it finished.
+    /// If the loop failed, the error is printed to stderr and the process is
+    /// exited with the [ERROR_EXIT_CODE](crate::ERROR_EXIT_CODE). By default this is implemented based on
+    /// [run_to_completion](RenderableCanvas::run_to_completion).
+    fn run<G: Game<Self> + 'static>(pixel_loop: PixelLoop<G, Self>) -> !
+    where
+        Self: Sized,
+    {
+        match Self::run_to_completion(pixel_loop) {
+            Ok((code, game)) => {
+                // Explicitly drop the game, as exiting the process skips all
+                // destructors.
+                drop(game);
+                std::process::exit(code);
+            }
+            Err(error) => {
+                eprintln!("Error: {error:?}");
+                std::process::exit(crate::ERROR_EXIT_CODE);
+            }
+        }
+    }
+
     /// Called before the main loop starts.
     fn begin(&mut self) -> Result<()> {
         Ok(())
@@ -6,14 +6,16 @@
 
 use super::{Canvas, RenderableCanvas};
 use crate::color::{Color, ColorAsByteSlice};
-use crate::input::PixelsInputState;
-use crate::NextLoopState;
+use crate::input::{InputState, PixelsInputState};
+use crate::{ExitRequest, Game, NextLoopState};
 use anyhow::{Context, Result};
 use pixels::{Pixels, SurfaceTexture};
+use std::marker::PhantomData;
 use std::ops::Range;
 use winit::dpi::LogicalSize;
 use winit::event::{Event, WindowEvent};
 use winit::event_loop::{ControlFlow, EventLoop};
+use winit::platform::run_return::EventLoopExtRunReturn;
 use winit::window::{Window, WindowBuilder};
 use winit_input_helper::WinitInputHelper;
 
@@ -30,12 +32,16 @@ struct WinitContext {
 /// using the pixels crate. It handles pixel data conversion between the
 /// internal Color type and the RGBA byte format required by pixels.
 ///
+/// By default the canvas is driven by a [PixelsInputState]. Any input
+/// implementation wrapping it may be used instead (See
+/// [with_input_type](PixelsCanvas::with_input_type)).
+///
 /// # Example
 ///
 /// ```
 /// let canvas = PixelsCanvas::new(640, 480, "pixel loop", false)?;
 /// ```
-pub struct PixelsCanvas {
+pub struct PixelsCanvas<I = PixelsInputState> {
     /// The scale factor of the canvas supplied by the user to create a more
     /// "blocky" pixel feeling.
     user_scale_factor: u32,
@@ -43,10 +49,8 @@ pub struct PixelsCanvas {
     context: Option<WinitContext>,
     /// The underlying pixels instance for window rendering
     pixels: Pixels,
-    /// The width of this canvas during the last loop
-    last_loop_width: u32,
-    /// The height of this canvas during the last loop
-    last_loop_height: u32,
+    /// The input implementation this canvas is driven with
+    input: PhantomData<I>,
 }
 
 impl PixelsCanvas {
@@ -110,19 +114,70 @@ impl PixelsCanvas {
             user_scale_factor: scale_factor.unwrap_or(1),
             context: Some(context),
             pixels,
-            last_loop_height: 0, // Zero initialized to cause initial update
-            last_loop_width: 0,  // Zero initialized to cause initial update
+            input: PhantomData,
         })
     }
 }
 
-impl PixelsCanvas {
-    fn take_context(&mut self) -> WinitContext {
-        self.context.take().unwrap()
+impl<I> PixelsCanvas<I> {
+    /// Changes the input implementation this canvas is driven with.
+    ///
+    /// Window events are handed to the wrapped [PixelsInputState] of the new
+    /// input implementation.
+    pub fn with_input_type<J>(self) -> PixelsCanvas<J>
+    where
+        J: InputState + AsMut<PixelsInputState>,
+    {
+        PixelsCanvas {
+            user_scale_factor: self.user_scale_factor,
+            context: self.context,
+            pixels: self.pixels,
+            input: PhantomData,
+        }
+    }
+
+    /// Maps a physical position within the window to a position on the
+    /// canvas, if it is within its bounds.
+    fn physical_pos_to_canvas_pos(&self, x: f64, y: f64) -> Option<(u32, u32)> {
+        if let Ok((x, y)) = self.pixels.window_pos_to_pixel((x as f32, y as f32)) {
+            Some((x as u32, y as u32))
+        } else {
+            None
+        }
+    }
+
+    fn take_context(&mut self) -> Result<WinitContext> {
+        self.context
+            .take()
+            .context("PixelsCanvas can only be run once, as its window is closed afterwards")
+    }
+
+    /// Resizes the surface and the buffer of pixels, to match the given
+    /// physical dimensions of the window.
+    fn try_resize_surface(
+        &mut self,
+        width: u32,
+        height: u32,
+        window_scale_factor: Option<f64>,
+    ) -> Result<()> {
+        self.pixels
+            .resize_surface(width, height)
+            .context("resize pixels surface")?;
+
+        // First scale the display size by the window scale factor, then scale
+        // by the user factor as well.
+        let display_scaled_width = (width as f64 / window_scale_factor.unwrap_or(1.0)) as u32;
+        let display_scaled_height = (height as f64 / window_scale_factor.unwrap_or(1.0)) as u32;
+        let user_scaled_width = display_scaled_width / self.user_scale_factor;
+        let user_scaled_height = display_scaled_height / self.user_scale_factor;
+        self.pixels
+            .resize_buffer(user_scaled_width, user_scaled_height)
+            .context("resize pixels buffer")?;
+        Ok(())
     }
 }
 
-impl Canvas for PixelsCanvas {
+impl<I> Canvas for PixelsCanvas<I> {
     fn width(&self) -> u32 {
         self.pixels.texture().width()
     }
@@ -145,17 +200,11 @@ impl Canvas for PixelsCanvas {
     }
 }
 
-impl RenderableCanvas for PixelsCanvas {
-    type Input = PixelsInputState;
-
-    // @TODO: Move to input when handling mouse control there
-    // fn physical_pos_to_canvas_pos(&self, x: f64, y: f64) -> Option<(u32, u32)> {
-    //     if let Ok((x, y)) = self.pixels.window_pos_to_pixel((x as f32, y as f32)) {
-    //         Some((x as u32, y as u32))
-    //     } else {
-    //         None
-    //     }
-    // }
+impl<I> RenderableCanvas for PixelsCanvas<I>
+where
+    I: InputState + AsMut<PixelsInputState>,
+{
+    type Input = I;
 
     fn render(&mut self) -> Result<()> {
         self.pixels
@@ -165,51 +214,45 @@ impl RenderableCanvas for PixelsCanvas {
     }
 
     fn resize_surface(&mut self, width: u32, height: u32, window_scale_factor: Option<f64>) {
-        self.pixels
-            .resize_surface(width, height)
+        self.try_resize_surface(width, height, window_scale_factor)
             .expect("to be able to resize surface");
-
-        // First scale the display size by the window scale factor, then scale
-        // by the user factor as well.
-        let display_scaled_width = (width as f64 / window_scale_factor.unwrap_or(1.0)) as u32;
-        let display_scaled_height = (height as f64 / window_scale_factor.unwrap_or(1.0)) as u32;
-        let user_scaled_width = display_scaled_width / self.user_scale_factor;
-        let user_scaled_height = display_scaled_height / self.user_scale_factor;
-        self.pixels
-            .resize_buffer(user_scaled_width, user_scaled_height)
-            .expect("to be able to resize buffer");
     }
 
-    /// Run the pixel loop, handling events and rendering.
+    /// Run the pixel loop, handling events and rendering, until it is exited.
     ///
     /// This implementation overrides the generic pixel_loop implementation, to
-    /// handle the winit event_loop properly.
-    fn run<State: 'static>(mut pixel_loop: crate::PixelLoop<State, Self>) -> !
+    /// handle the winit event_loop properly. It utilizes winit's `run_return`
+    /// and is therefore only available on desktop platforms.
+    fn run_to_completion<G: Game<Self> + 'static>(
+        mut pixel_loop: crate::PixelLoop<G, Self>,
+    ) -> Result<(i32, G)>
     where
         Self: Sized,
     {
-        // We may take the context here, as we are never returning from this
-        // function again.
-        let context = pixel_loop.canvas.take_context();
+        // The context is consumed by this run, as the window is closed once
+        // the event loop returns.
+        let mut context = pixel_loop.canvas.take_context()?;
 
-        pixel_loop.begin().context("initialize pixel_loop").unwrap();
+        pixel_loop.begin().context("initialize pixel_loop")?;
         let mut exit_code = 0i32;
-        context.event_loop.run(move |event, _, control_flow| {
-            pixel_loop.input_state.handle_new_event(&event);
+        let mut loop_error = None;
+        context.event_loop.run_return(|event, _, control_flow| {
+            pixel_loop.input_state.as_mut().handle_new_event(&event);
             match event {
                 Event::MainEventsCleared => {
-                    let next = pixel_loop
-                        .next_loop()
-                        .context("run next pixel loop")
-                        .unwrap();
+                    let next = match pixel_loop.next_loop().context("run next pixel loop") {
+                        Ok(next) => next,
+                        Err(error) => {
+                            loop_error = Some(error);
+                            *control_flow = ControlFlow::Exit;
+                            return;
+                        }
+                    };
                     if let NextLoopState::Exit(code) = next {
                         exit_code = code;
                         *control_flow = ControlFlow::Exit;
                         return;
                     }
-                    // Track last communicated canvas size
-                    pixel_loop.canvas.last_loop_width = pixel_loop.canvas.width();
-                    pixel_loop.canvas.last_loop_height = pixel_loop.canvas.height();
                 }
                 Event::WindowEvent {
                     event: win_event, ..
@@ -217,35 +260,54 @@ impl RenderableCanvas for PixelsCanvas {
                     // Handle window resize events and correct buffer and
                     // surface sizes
                     WindowEvent::Resized(physical_size) => {
-                        pixel_loop.canvas.resize_surface(
+                        if let Err(error) = pixel_loop.canvas.try_resize_surface(
                             physical_size.width,
                             physical_size.height,
                             Some(context.window.scale_factor()),
-                        );
+                        ) {
+                            loop_error = Some(error);
+                            *control_flow = ControlFlow::Exit;
+                        }
+                    }
+                    // Map the cursor position to canvas pixels for the
+                    // input state
+                    WindowEvent::CursorMoved { position, .. } => {
+                        let canvas_position = pixel_loop
+                            .canvas
+                            .physical_pos_to_canvas_pos(position.x, position.y);
+                        pixel_loop
+                            .input_state
+                            .as_mut()
+                            .handle_mouse_position(canvas_position);
                     }
+                    // Let the game decide, whether to close the window
                     WindowEvent::CloseRequested => {
-                        exit_code = 0;
-                        *control_flow = ControlFlow::Exit;
-                        return;
+                        match pixel_loop
+                            .request_exit(ExitRequest::Close)
+                            .context("request exit of pixel loop")
+                        {
+                            Ok(NextLoopState::Exit(code)) => {
+                                exit_code = code;
+                                *control_flow = ControlFlow::Exit;
+                            }
+                            Ok(NextLoopState::Continue) => {}
+                            Err(error) => {
+                                loop_error = Some(error);
+                                *control_flow = ControlFlow::Exit;
+                            }
+                        }
                     }
                     _ => {}
                 },
-                Event::LoopDestroyed => {
-                    pixel_loop
-                        .finish(exit_code)
-                        .context("finish pixel loop")
-                        .unwrap();
-                }
                 _ => {}
             }
         });
-    }
 
-    fn did_resize(&self) -> Option<(u32, u32)> {
-        if self.last_loop_width != self.width() || self.last_loop_height != self.height() {
-            Some((self.width(), self.height()))
-        } else {
-            None
+        if let Some(error) = loop_error {
+            return Err(pixel_loop.abort(error));
         }
+
+        pixel_loop.finish(exit_code).context("finish pixel loop")?;
+        Ok((exit_code, pixel_loop.into_game()))
     }
 }
@@ -0,0 +1,284 @@
+//! Clock abstraction driving the fixed timestep of the game loop.
+//!
+//! The [Clock] measures the time passed between two frames based on an
+//! exchangeable [TimeSource]. On top of the raw measurement it provides
+//! control over the flow of time, like scaling, pausing and single stepping,
+//! which is honoured by the update accumulator of the
+//! [PixelLoop](crate::PixelLoop).
+//!
+//! A [MockTimeSource] can be used to advance time manually, eg. within tests.
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Source of monotonic time used by a [Clock].
+pub trait TimeSource {
+    /// Returns the time passed since an arbitrary, but fixed point in time.
+    fn now(&self) -> Duration;
+}
+
+/// Time source based on the monotonic system clock ([Instant]).
+pub struct SystemTimeSource {
+    origin: Instant,
+}
+
+impl Default for SystemTimeSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemTimeSource {
+    /// Creates a new system time source starting at the current instant.
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl TimeSource for SystemTimeSource {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+/// Time source, which is only advanced manually.
+///
+/// All clones of a mock time source share the same time. A clone can therefore
+/// be handed to a [Clock], while the original is used to advance the time.
+///
+/// # Example
+/// ```
+/// use pixel_loop::clock::{Clock, MockTimeSource};
+/// use std::time::Duration;
+///
+/// let time = MockTimeSource::new();
+/// let mut clock = Clock::new(time.clone());
+///
+/// time.advance(Duration::from_millis(10));
+/// assert_eq!(clock.tick(Duration::from_millis(5)), Duration::from_millis(10));
+/// ```
+#[derive(Clone, Default)]
+pub struct MockTimeSource {
+    now: Rc<Cell<Duration>>,
+}
+
+impl MockTimeSource {
+    /// Creates a new mock time source starting at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advances the time by the given duration.
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+
+    /// Sets the time to the given duration since the start.
+    ///
+    /// Time must not go backwards. A smaller value than the current time is
+    /// treated as if no time has passed.
+    pub fn set(&self, now: Duration) {
+        self.now.set(now);
+    }
+}
+
+impl TimeSource for MockTimeSource {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+/// Clock measuring the time between frames of the game loop.
+///
+/// The clock is part of the [EngineEnvironment](crate::EngineEnvironment) and
+/// can therefore be controlled from within update and render functions.
+///
+/// # Example
+/// ```
+/// use pixel_loop::clock::{Clock, MockTimeSource};
+/// use std::time::Duration;
+///
+/// let time = MockTimeSource::new();
+/// let mut clock = Clock::new(time.clone()).with_time_scale(0.5);
+///
+/// time.advance(Duration::from_millis(20));
+/// assert_eq!(clock.tick(Duration::from_millis(5)), Duration::from_millis(10));
+///
+/// clock.pause();
+/// time.advance(Duration::from_millis(20));
+/// assert_eq!(clock.tick(Duration::from_millis(5)), Duration::ZERO);
+///
+/// // A single step advances exactly one update timestep.
+/// clock.step();
+/// time.advance(Duration::from_millis(20));
+/// assert_eq!(clock.tick(Duration::from_millis(5)), Duration::from_millis(5));
+/// ```
+pub struct Clock {
+    /// The source of the measured time
+    source: Box<dyn TimeSource>,
+    /// The time of the source during the last tick
+    last_tick: Duration,
+    /// Factor applied to all measured frame deltas
+    time_scale: f64,
+    /// Whether the flow of time is currently paused
+    paused: bool,
+    /// Number of single update steps requested while being paused
+    pending_steps: u32,
+    /// The maximal measured (unscaled) delta of one frame
+    max_frame_delta: Duration,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new(SystemTimeSource::new())
+    }
+}
+
+impl Clock {
+    /// Creates a new clock based on the given time source.
+    ///
+    /// By default the time scale is `1.0` and the maximal frame delta is
+    /// 100ms.
+    pub fn new<T: TimeSource + 'static>(source: T) -> Self {
+        Self {
+            last_tick: source.now(),
+            source: Box::new(source),
+            time_scale: 1.0,
+            paused: false,
+            pending_steps: 0,
+            max_frame_delta: Duration::from_millis(100),
+        }
+    }
+
+    /// Sets the factor applied to the flow of time.
+    ///
+    /// A factor of `2.0` doubles the speed of the simulation, while `0.5` runs
+    /// it in slow motion.
+    pub fn with_time_scale(mut self, time_scale: f64) -> Self {
+        self.set_time_scale(time_scale);
+        self
+    }
+
+    /// Sets the maximal measured delta of one frame.
+    ///
+    /// Longer frames are clamped to this value, to prevent the update loop
+    /// from spiraling out of control after stalls. The time scale is applied
+    /// afterwards, so fast forwarding is not limited by it.
+    ///
+    /// # Example
+    /// ```
+    /// use pixel_loop::clock::{Clock, MockTimeSource};
+    /// use std::time::Duration;
+    ///
+    /// let time = MockTimeSource::new();
+    /// let mut clock = Clock::new(time.clone())
+    ///     .with_max_frame_delta(Duration::from_millis(100))
+    ///     .with_time_scale(10.0);
+    ///
+    /// time.advance(Duration::from_millis(16));
+    /// assert_eq!(clock.tick(Duration::from_millis(5)), Duration::from_millis(160));
+    ///
+    /// // A stall of one second is clamped before scaling.
+    /// time.advance(Duration::from_secs(1));
+    /// assert_eq!(clock.tick(Duration::from_millis(5)), Duration::from_secs(1));
+    /// ```
+    pub fn with_max_frame_delta(mut self, max_frame_delta: Duration) -> Self {
+        self.max_frame_delta = max_frame_delta;
+        self
+    }
+
+    /// Replaces the time source of this clock.
+    ///
+    /// The next tick measures the time passed since this call.
+    pub fn set_time_source<T: TimeSource + 'static>(&mut self, source: T) {
+        self.last_tick = source.now();
+        self.source = Box::new(source);
+    }
+
+    /// Get the factor applied to the flow of time.
+    pub fn time_scale(&self) -> f64 {
+        self.time_scale
+    }
+
+    /// Set the factor applied to the flow of time.
+    ///
+    /// Negative values and NaN are treated as `0.0`, while infinity is
+    /// clamped to the largest finite factor.
+    pub fn set_time_scale(&mut self, time_scale: f64) {
+        self.time_scale = time_scale.clamp(0.0, f64::MAX);
+    }
+
+    /// Get the maximal measured delta of one frame.
+    pub fn max_frame_delta(&self) -> Duration {
+        self.max_frame_delta
+    }
+
+    /// Set the maximal measured delta of one frame.
+    pub fn set_max_frame_delta(&mut self, max_frame_delta: Duration) {
+        self.max_frame_delta = max_frame_delta;
+    }
+
+    /// Pauses the flow of time.
+    ///
+    /// While paused no updates are executed, unless explicitly requested using
+    /// [step](Clock::step). Rendering continues with a delta of zero.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes the flow of time after a pause.
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.pending_steps = 0;
+    }
+
+    /// Toggles between paused and running.
+    pub fn toggle_pause(&mut self) {
+        if self.paused {
+            self.resume();
+        } else {
+            self.pause();
+        }
+    }
+
+    /// Check if the flow of time is currently paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Requests a single update step while being paused.
+    ///
+    /// Has no effect, if the clock is not paused.
+    pub fn step(&mut self) {
+        if self.paused {
+            self.pending_steps += 1;
+        }
+    }
+
+    /// Measures the time since the last tick and returns the delta the game
+    /// loop should advance by.
+    ///
+    /// The measured time is clamped to the maximal frame delta, before the
+    /// time scale is applied. Scaled deltas exceeding the range of a
+    /// [Duration] saturate. While paused, a pending single step advances by
+    /// exactly one `update_timestep`.
+    pub fn tick(&mut self, update_timestep: Duration) -> Duration {
+        let now = self.source.now();
+        let raw_delta = now.saturating_sub(self.last_tick);
+        self.last_tick = now;
+
+        if self.paused {
+            if self.pending_steps > 0 {
+                self.pending_steps -= 1;
+                return update_timestep;
+            }
+            return Duration::ZERO;
+        }
+
+        let delta = raw_delta.min(self.max_frame_delta);
+        Duration::try_from_secs_f64(delta.as_secs_f64() * self.time_scale).unwrap_or(Duration::MAX)
+    }
+}
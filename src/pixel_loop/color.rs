@@ -249,6 +249,112 @@ impl From<HslColor> for Color {
     }
 }
 
+/// Modes of combining a (source) color drawn onto a canvas with the
+/// (destination) color already present at that position.
+///
+/// # Examples
+/// ```
+/// use pixel_loop::color::{BlendMode, Color};
+///
+/// let background = Color::from_rgb(0, 0, 200);
+/// let half_red = Color::from_rgba(200, 0, 0, 128);
+///
+/// assert_eq!(BlendMode::Replace.blend(half_red, background), half_red);
+/// assert_eq!(
+///     BlendMode::Alpha.blend(half_red, background),
+///     Color::from_rgb(100, 0, 100)
+/// );
+/// assert_eq!(
+///     BlendMode::ColorKey(Color::from_rgb(200, 0, 0)).blend(half_red, background),
+///     background
+/// );
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum BlendMode {
+    /// The source color replaces the destination color, including its alpha
+    /// channel
+    #[default]
+    Replace,
+    /// The source color is drawn over the destination color according to its
+    /// alpha channel
+    Alpha,
+    /// The source color, weighted by its alpha channel, is added to the
+    /// destination color
+    Additive,
+    /// The destination color is multiplied by the source color, weighted by
+    /// its alpha channel
+    Multiply,
+    /// Source colors matching the given key color (ignoring alpha) are
+    /// transparent, all others replace the destination color
+    ColorKey(Color),
+}
+
+impl BlendMode {
+    /// Combines a source color with a destination color using this mode.
+    ///
+    /// # Arguments
+    /// * `src` - The color being drawn
+    /// * `dst` - The color already present on the canvas
+    pub fn blend(self, src: Color, dst: Color) -> Color {
+        match self {
+            BlendMode::Replace => src,
+            BlendMode::Alpha => match src.a {
+                255 => src,
+                0 => dst,
+                _ => alpha_over(src, dst),
+            },
+            BlendMode::Additive => {
+                let add = |s: u8, d: u8| d.saturating_add(mul_u8(s, src.a));
+                Color::from_rgba(
+                    add(src.r, dst.r),
+                    add(src.g, dst.g),
+                    add(src.b, dst.b),
+                    dst.a,
+                )
+            }
+            BlendMode::Multiply => {
+                let product = Color::from_rgba(
+                    mul_u8(src.r, dst.r),
+                    mul_u8(src.g, dst.g),
+                    mul_u8(src.b, dst.b),
+                    src.a,
+                );
+                alpha_over(product, dst)
+            }
+            BlendMode::ColorKey(key) => {
+                if (src.r, src.g, src.b) == (key.r, key.g, key.b) {
+                    dst
+                } else {
+                    src
+                }
+            }
+        }
+    }
+}
+
+/// Multiplies two color components in the range [0-255] with rounding.
+fn mul_u8(a: u8, b: u8) -> u8 {
+    ((a as u32 * b as u32 + 127) / 255) as u8
+}
+
+/// Draws a non premultiplied source color over a destination color.
+fn alpha_over(src: Color, dst: Color) -> Color {
+    let src_a = src.a as u32;
+    let dst_a = mul_u8(dst.a, 255 - src.a) as u32;
+    let out_a = src_a + dst_a;
+    if out_a == 0 {
+        return Color::from_rgba(0, 0, 0, 0);
+    }
+
+    let mix = |s: u8, d: u8| ((s as u32 * src_a + d as u32 * dst_a + out_a / 2) / out_a) as u8;
+    Color::from_rgba(
+        mix(src.r, dst.r),
+        mix(src.g, dst.g),
+        mix(src.b, dst.b),
+        out_a as u8,
+    )
+}
+
 /// A color representation in HSL (Hue, Saturation, Lightness) color space.
 pub struct HslColor {
     /// Hue component [0-360]
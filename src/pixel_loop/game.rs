@@ -0,0 +1,397 @@
+//! Game abstraction driven by the pixel loop.
+//!
+//! A [Game] bundles the state of an application together with the functions
+//! operating on it. The [PixelLoop](crate::PixelLoop) calls into it for every
+//! update and render step, as well as for certain lifecycle events.
+//!
+//! Games may either be implemented as a type implementing the [Game] trait, or
+//! by combining a state with update and render closures using [FnGame].
+
+use crate::canvas::RenderableCanvas;
+use crate::{EngineEnvironment, ExitRequest, FrameInfo, NextLoopState};
+use anyhow::Result;
+
+/// Trait representing a game, which can be driven by the pixel loop.
+///
+/// Only [update](Game::update) and [render](Game::render) need to be
+/// implemented. All the other lifecycle hooks default to doing nothing.
+///
+/// # Example
+/// ```
+/// use pixel_loop::canvas::{Canvas, HeadlessCanvas, RenderableCanvas};
+/// use pixel_loop::color::Color;
+/// use pixel_loop::input::HeadlessInputState;
+/// use pixel_loop::{EngineEnvironment, FrameInfo, Game, NextLoopState};
+/// use anyhow::Result;
+///
+/// struct Flash {
+///     color: Color,
+/// }
+///
+/// impl<C: RenderableCanvas> Game<C> for Flash {
+///     fn update(&mut self, env: &mut EngineEnvironment, input: &C::Input, canvas: &mut C) -> Result<NextLoopState> {
+///         self.color.r = self.color.r.wrapping_add(1);
+///         Ok(NextLoopState::Continue)
+///     }
+///
+///     fn render(&mut self, env: &mut EngineEnvironment, input: &C::Input, canvas: &mut C, frame: FrameInfo) -> Result<NextLoopState> {
+///         canvas.clear_screen(&self.color);
+///         canvas.render()?;
+///         Ok(NextLoopState::Continue)
+///     }
+/// }
+///
+/// let (code, flash) = pixel_loop::run_game_to_completion(
+///     60,
+///     Flash { color: Color::from_rgb(0, 0, 0) },
+///     HeadlessInputState::new(),
+///     HeadlessCanvas::new(8, 8).with_frames(3),
+/// )?;
+/// assert_eq!(code, 0);
+/// assert_eq!(flash.color.r, 3);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub trait Game<CanvasImpl: RenderableCanvas> {
+    /// Called at a fixed timestep to update the game state.
+    ///
+    /// # Arguments
+    /// * `env` - Global engine environment containing shared resources
+    /// * `input` - Reference to the current input state
+    /// * `canvas` - Mutable reference to the rendering canvas
+    ///
+    /// # Returns
+    /// * `NextLoopState` - Determines if the game loop should continue or exit
+    fn update(
+        &mut self,
+        env: &mut EngineEnvironment,
+        input: &CanvasImpl::Input,
+        canvas: &mut CanvasImpl,
+    ) -> Result<NextLoopState>;
+
+    /// Called once per frame to render the game state.
+    ///
+    /// # Arguments
+    /// * `env` - Global engine environment containing shared resources
+    /// * `input` - Reference to the current input state
+    /// * `canvas` - Mutable reference to the rendering canvas
+    /// * `frame` - Timing information about the current frame
+    ///
+    /// # Returns
+    /// * `NextLoopState` - Determines if the game loop should continue or exit
+    fn render(
+        &mut self,
+        env: &mut EngineEnvironment,
+        input: &CanvasImpl::Input,
+        canvas: &mut CanvasImpl,
+        frame: FrameInfo,
+    ) -> Result<NextLoopState>;
+
+    /// Called once before the first frame, after input and canvas have been
+    /// initialized.
+    fn on_begin(&mut self, _env: &mut EngineEnvironment, _canvas: &mut CanvasImpl) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called before the next update, whenever the size of the canvas changed.
+    ///
+    /// It is called for the very first frame as well, with an `old` size of
+    /// `(0, 0)`, to allow initialization based on the canvas size.
+    ///
+    /// # Arguments
+    /// * `old` - The previous size of the canvas (width, height)
+    /// * `new` - The current size of the canvas (width, height)
+    fn on_resize(
+        &mut self,
+        _env: &mut EngineEnvironment,
+        _canvas: &mut CanvasImpl,
+        _old: (u32, u32),
+        _new: (u32, u32),
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called whenever the loop is requested to exit by the user or the
+    /// system, eg. by pressing Ctrl-C, closing the window or sending SIGTERM.
+    ///
+    /// This allows to ask for confirmation, to save the current state, or to
+    /// ignore the request by returning [NextLoopState::Continue]. By default
+    /// the loop is exited with the [conventional code](ExitRequest::code) of
+    /// the request.
+    ///
+    /// # Example
+    /// ```
+    /// use pixel_loop::canvas::{HeadlessCanvas, RenderableCanvas};
+    /// use pixel_loop::input::HeadlessInputState;
+    /// use pixel_loop::{EngineEnvironment, ExitRequest, FrameInfo, Game, NextLoopState};
+    /// use anyhow::Result;
+    ///
+    /// struct Editor {
+    ///     unsaved_changes: bool,
+    /// }
+    ///
+    /// impl<C: RenderableCanvas> Game<C> for Editor {
+    ///     fn update(&mut self, _env: &mut EngineEnvironment, _input: &C::Input, _canvas: &mut C) -> Result<NextLoopState> {
+    ///         Ok(NextLoopState::Continue)
+    ///     }
+    ///
+    ///     fn render(&mut self, _env: &mut EngineEnvironment, _input: &C::Input, _canvas: &mut C, _frame: FrameInfo) -> Result<NextLoopState> {
+    ///         Ok(NextLoopState::Continue)
+    ///     }
+    ///
+    ///     fn on_exit_request(&mut self, _env: &mut EngineEnvironment, _canvas: &mut C, request: ExitRequest) -> Result<NextLoopState> {
+    ///         if self.unsaved_changes {
+    ///             // Save and exit on the next request
+    ///             self.unsaved_changes = false;
+    ///             return Ok(NextLoopState::Continue);
+    ///         }
+    ///         Ok(NextLoopState::Exit(request.code()))
+    ///     }
+    /// }
+    ///
+    /// let input = HeadlessInputState::new()
+    ///     .with_exit_request(2, ExitRequest::Interrupt)
+    ///     .with_exit_request(5, ExitRequest::Interrupt);
+    /// let result = HeadlessCanvas::run_frames(pixel_loop::PixelLoop::from_game(
+    ///     60,
+    ///     Editor { unsaved_changes: true },
+    ///     input,
+    ///     HeadlessCanvas::new(8, 8).with_frames(10),
+    /// ))?;
+    ///
+    /// assert_eq!(result.exit_code, Some(130));
+    /// assert_eq!(result.frames, 6);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    fn on_exit_request(
+        &mut self,
+        _env: &mut EngineEnvironment,
+        _canvas: &mut CanvasImpl,
+        request: ExitRequest,
+    ) -> Result<NextLoopState> {
+        Ok(NextLoopState::Exit(request.code()))
+    }
+
+    /// Called whenever one of the other hooks returned an error, if the loop
+    /// is configured to use [ErrorPolicy::Hook](crate::ErrorPolicy::Hook).
+    ///
+    /// This allows to recover from errors by returning
+    /// [NextLoopState::Continue], to exit the loop with a specific code, or to
+    /// abort the loop by returning an error. By default the error is returned
+    /// unchanged.
+    fn on_error(
+        &mut self,
+        _env: &mut EngineEnvironment,
+        _canvas: &mut CanvasImpl,
+        error: anyhow::Error,
+    ) -> Result<NextLoopState> {
+        Err(error)
+    }
+
+    /// Called once the loop has been exited, before input and canvas are
+    /// finalized.
+    fn on_exit(&mut self, _env: &mut EngineEnvironment, _code: i32) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A [Game] composed of a state and update and render closures.
+///
+/// This is the representation used by [run](crate::run) and friends. Any
+/// function or closure with a matching signature may be used. In contrast to
+/// bare function pointers closures may capture their environment, eg. to
+/// access some configuration.
+///
+/// # Example
+/// ```
+/// use pixel_loop::canvas::{Canvas, HeadlessCanvas, RenderableCanvas};
+/// use pixel_loop::input::HeadlessInputState;
+/// use pixel_loop::{EngineEnvironment, FnGame, FrameInfo, NextLoopState};
+/// use anyhow::Result;
+///
+/// fn update(
+///     _env: &mut EngineEnvironment,
+///     updates: &mut u32,
+///     _input: &HeadlessInputState,
+///     _canvas: &mut HeadlessCanvas,
+/// ) -> Result<NextLoopState> {
+///     *updates += 1;
+///     Ok(NextLoopState::Continue)
+/// }
+///
+/// fn render(
+///     _env: &mut EngineEnvironment,
+///     _updates: &mut u32,
+///     _input: &HeadlessInputState,
+///     canvas: &mut HeadlessCanvas,
+///     _frame: FrameInfo,
+/// ) -> Result<NextLoopState> {
+///     canvas.render()?;
+///     Ok(NextLoopState::Continue)
+/// }
+///
+/// let game = FnGame::new(0u32, update, render);
+/// ```
+pub struct FnGame<State, U, R> {
+    /// The game state passed to update and render
+    pub state: State,
+    update: U,
+    render: R,
+    exit_request: Option<ExitRequestHandler<State>>,
+    error: Option<ErrorHandler<State>>,
+}
+
+/// Function deciding about requests to exit the loop (See
+/// [Game::on_exit_request]).
+type ExitRequestHandler<State> =
+    Box<dyn FnMut(&mut EngineEnvironment, &mut State, ExitRequest) -> Result<NextLoopState>>;
+
+/// Function deciding about errors returned by update and render (See
+/// [Game::on_error]).
+type ErrorHandler<State> =
+    Box<dyn FnMut(&mut EngineEnvironment, &mut State, anyhow::Error) -> Result<NextLoopState>>;
+
+impl<State, U, R> FnGame<State, U, R> {
+    /// Creates a new game from the given state and update and render
+    /// functions.
+    ///
+    /// # Arguments
+    /// * `state` - Initial game state
+    /// * `update` - Update function called at fixed timestep
+    /// * `render` - Render function called as often as possible
+    pub fn new(state: State, update: U, render: R) -> Self {
+        Self {
+            state,
+            update,
+            render,
+            exit_request: None,
+            error: None,
+        }
+    }
+
+    /// Sets a function deciding about requests to exit the loop, eg. due to
+    /// Ctrl-C or closing the window (See [Game::on_exit_request]).
+    ///
+    /// # Example
+    /// ```
+    /// use pixel_loop::canvas::HeadlessCanvas;
+    /// use pixel_loop::input::HeadlessInputState;
+    /// use pixel_loop::{EngineEnvironment, ExitRequest, FnGame, FrameInfo, NextLoopState, PixelLoop};
+    /// # use anyhow::Result;
+    /// # fn update(_: &mut EngineEnvironment, _: &mut u32, _: &HeadlessInputState, _: &mut HeadlessCanvas) -> Result<NextLoopState> {
+    /// #     Ok(NextLoopState::Continue)
+    /// # }
+    /// # fn render(_: &mut EngineEnvironment, _: &mut u32, _: &HeadlessInputState, _: &mut HeadlessCanvas, _: FrameInfo) -> Result<NextLoopState> {
+    /// #     Ok(NextLoopState::Continue)
+    /// # }
+    ///
+    /// let game = FnGame::new(0u32, update, render).with_exit_request_handler(|_, ignored, request| {
+    ///     // Only let the window be closed
+    ///     if request == ExitRequest::Close {
+    ///         return Ok(NextLoopState::Exit(request.code()));
+    ///     }
+    ///     *ignored += 1;
+    ///     Ok(NextLoopState::Continue)
+    /// });
+    ///
+    /// let input = HeadlessInputState::new().with_exit_request(1, ExitRequest::Interrupt);
+    /// let result = HeadlessCanvas::run_frames(PixelLoop::from_game(
+    ///     60,
+    ///     game,
+    ///     input,
+    ///     HeadlessCanvas::new(8, 8).with_frames(3),
+    /// ))?;
+    ///
+    /// assert_eq!(result.exit_code, None);
+    /// assert_eq!(result.state.state, 1);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn with_exit_request_handler<E>(mut self, handler: E) -> Self
+    where
+        E: FnMut(&mut EngineEnvironment, &mut State, ExitRequest) -> Result<NextLoopState>
+            + 'static,
+    {
+        self.exit_request = Some(Box::new(handler));
+        self
+    }
+
+    /// Sets a function deciding about errors returned by update and render
+    /// (See [Game::on_error]).
+    ///
+    /// It is only called, if the loop is configured to use
+    /// [ErrorPolicy::Hook](crate::ErrorPolicy::Hook) (See
+    /// [with_error_policy](crate::PixelLoop::with_error_policy)).
+    pub fn with_error_handler<E>(mut self, handler: E) -> Self
+    where
+        E: FnMut(&mut EngineEnvironment, &mut State, anyhow::Error) -> Result<NextLoopState>
+            + 'static,
+    {
+        self.error = Some(Box::new(handler));
+        self
+    }
+
+    /// Consumes the game and returns the owned game state.
+    pub fn into_state(self) -> State {
+        self.state
+    }
+}
+
+impl<State, U, R, CanvasImpl> Game<CanvasImpl> for FnGame<State, U, R>
+where
+    CanvasImpl: RenderableCanvas,
+    U: FnMut(
+        &mut EngineEnvironment,
+        &mut State,
+        &CanvasImpl::Input,
+        &mut CanvasImpl,
+    ) -> Result<NextLoopState>,
+    R: FnMut(
+        &mut EngineEnvironment,
+        &mut State,
+        &CanvasImpl::Input,
+        &mut CanvasImpl,
+        FrameInfo,
+    ) -> Result<NextLoopState>,
+{
+    fn update(
+        &mut self,
+        env: &mut EngineEnvironment,
+        input: &CanvasImpl::Input,
+        canvas: &mut CanvasImpl,
+    ) -> Result<NextLoopState> {
+        (self.update)(env, &mut self.state, input, canvas)
+    }
+
+    fn render(
+        &mut self,
+        env: &mut EngineEnvironment,
+        input: &CanvasImpl::Input,
+        canvas: &mut CanvasImpl,
+        frame: FrameInfo,
+    ) -> Result<NextLoopState> {
+        (self.render)(env, &mut self.state, input, canvas, frame)
+    }
+
+    fn on_exit_request(
+        &mut self,
+        env: &mut EngineEnvironment,
+        _canvas: &mut CanvasImpl,
+        request: ExitRequest,
+    ) -> Result<NextLoopState> {
+        match self.exit_request.as_mut() {
+            Some(handler) => handler(env, &mut self.state, request),
+            None => Ok(NextLoopState::Exit(request.code())),
+        }
+    }
+
+    fn on_error(
+        &mut self,
+        env: &mut EngineEnvironment,
+        _canvas: &mut CanvasImpl,
+        error: anyhow::Error,
+    ) -> Result<NextLoopState> {
+        match self.error.as_mut() {
+            Some(handler) => handler(env, &mut self.state, error),
+            None => Err(error),
+        }
+    }
+}
@@ -0,0 +1,436 @@
+//! Mapping of user defined actions to keys and mouse buttons.
+//!
+//! Instead of checking for specific keys throughout update functions, games
+//! can define their own set of actions (eg. `Jump` or `MoveX`), and bind them
+//! to one or more keys and mouse buttons using an [ActionMap]. Bindings can be
+//! changed at runtime, as well as stored to and loaded from a simple text
+//! config.
+//!
+//! # Config format
+//! ```text
+//! # Button actions are bound to a list of keys and mouse buttons
+//! jump = Space, Up, Mouse:Left
+//! # Axis actions list the negative and positive bindings separated by a slash
+//! move_x = Left, A / Right, D
+//! ```
+//!
+//! Keys are named as their [KeyboardKey] variant. Mouse buttons are prefixed
+//! with `Mouse:`. Empty lines and lines starting with `#` are ignored.
+
+use super::{KeyboardKey, KeyboardState, MouseButton, MouseState};
+use anyhow::{anyhow, bail, Context, Result};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A single key or mouse button an action can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding {
+    /// A key of the keyboard
+    Key(KeyboardKey),
+    /// A button of the mouse
+    MouseButton(MouseButton),
+}
+
+impl Binding {
+    /// Finds a binding, which has been pressed this frame.
+    ///
+    /// This can be used to let the user choose a new binding for an action,
+    /// eg. within a settings menu.
+    pub fn pressed<I: KeyboardState + MouseState>(input: &I) -> Option<Binding> {
+        KeyboardKey::ALL
+            .into_iter()
+            .map(Binding::Key)
+            .chain(MouseButton::ALL.into_iter().map(Binding::MouseButton))
+            .find(|binding| binding.is_pressed(input))
+    }
+
+    /// Checks if the bound key or button was pressed this frame.
+    pub fn is_pressed<I: KeyboardState + MouseState>(&self, input: &I) -> bool {
+        match self {
+            Binding::Key(key) => input.is_key_pressed(*key),
+            Binding::MouseButton(button) => input.is_mouse_button_pressed(*button),
+        }
+    }
+
+    /// Checks if the bound key or button is currently being held down.
+    pub fn is_down<I: KeyboardState + MouseState>(&self, input: &I) -> bool {
+        match self {
+            Binding::Key(key) => input.is_key_down(*key),
+            Binding::MouseButton(button) => input.is_mouse_button_down(*button),
+        }
+    }
+
+    /// Checks if the bound key or button was released this frame.
+    pub fn is_released<I: KeyboardState + MouseState>(&self, input: &I) -> bool {
+        match self {
+            Binding::Key(key) => input.is_key_released(*key),
+            Binding::MouseButton(button) => input.is_mouse_button_released(*button),
+        }
+    }
+}
+
+impl From<KeyboardKey> for Binding {
+    fn from(key: KeyboardKey) -> Self {
+        Binding::Key(key)
+    }
+}
+
+impl From<MouseButton> for Binding {
+    fn from(button: MouseButton) -> Self {
+        Binding::MouseButton(button)
+    }
+}
+
+impl std::fmt::Display for Binding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{key}"),
+            Binding::MouseButton(button) => write!(f, "Mouse:{button}"),
+        }
+    }
+}
+
+impl FromStr for Binding {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        match name.strip_prefix("Mouse:") {
+            Some(button) => Ok(Binding::MouseButton(button.parse()?)),
+            None => Ok(Binding::Key(name.parse()?)),
+        }
+    }
+}
+
+/// The bindings of a single action.
+#[derive(Debug, Clone, PartialEq)]
+enum ActionBindings {
+    /// Action triggered by any of the bindings
+    Button(Vec<Binding>),
+    /// Action composed of a negative and a positive direction
+    Axis {
+        negative: Vec<Binding>,
+        positive: Vec<Binding>,
+    },
+}
+
+/// Maps user defined actions to keys and mouse buttons.
+///
+/// Actions are usually represented by a fieldless enum. To store and load the
+/// bindings as config, the action type needs to implement [Display] and
+/// [FromStr].
+///
+/// # Example
+/// ```
+/// use pixel_loop::canvas::HeadlessCanvas;
+/// use pixel_loop::input::{ActionMap, HeadlessInputState, KeyboardKey, MouseButton};
+/// use pixel_loop::NextLoopState;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// enum Action {
+///     Jump,
+///     MoveX,
+/// }
+///
+/// let actions = ActionMap::new()
+///     .with_binding(Action::Jump, KeyboardKey::Space)
+///     .with_binding(Action::Jump, MouseButton::Left)
+///     .with_axis(Action::MoveX, [KeyboardKey::Left, KeyboardKey::A], [KeyboardKey::Right, KeyboardKey::D]);
+///
+/// let input = HeadlessInputState::new()
+///     .with_mouse_click(0, MouseButton::Left)
+///     .with_key_press(0, KeyboardKey::D);
+///
+/// let result = pixel_loop::run_headless(
+///     60,
+///     (0, 0.0),
+///     input,
+///     HeadlessCanvas::new(8, 8).with_frames(1),
+///     move |_, (jumps, x), input, _| {
+///         if actions.is_action_pressed(input, &Action::Jump) {
+///             *jumps += 1;
+///         }
+///         *x += actions.axis(input, &Action::MoveX);
+///         Ok(NextLoopState::Continue)
+///     },
+///     |_, _, _, _, _| Ok(NextLoopState::Continue),
+/// )?;
+///
+/// assert_eq!(result.state, (1, 1.0));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionMap<A> {
+    /// Bindings of all actions in the order they have been defined
+    actions: Vec<(A, ActionBindings)>,
+}
+
+impl<A: PartialEq> Default for ActionMap<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: PartialEq> ActionMap<A> {
+    /// Creates a new action map without any bindings.
+    pub fn new() -> Self {
+        Self { actions: vec![] }
+    }
+
+    /// Adds a binding to the given action.
+    pub fn with_binding<B: Into<Binding>>(mut self, action: A, binding: B) -> Self {
+        self.bind(action, binding);
+        self
+    }
+
+    /// Defines the given action as axis composed of a negative and a positive
+    /// direction (See [axis](ActionMap::axis)).
+    pub fn with_axis<N, P>(mut self, action: A, negative: N, positive: P) -> Self
+    where
+        N: IntoIterator,
+        N::Item: Into<Binding>,
+        P: IntoIterator,
+        P::Item: Into<Binding>,
+    {
+        self.bind_axis(action, negative, positive);
+        self
+    }
+
+    /// Adds a binding to the given action.
+    ///
+    /// If the action has been defined as an axis before, it is turned into a
+    /// button action.
+    pub fn bind<B: Into<Binding>>(&mut self, action: A, binding: B) {
+        let binding = binding.into();
+        match self.get_mut(&action) {
+            Some(ActionBindings::Button(bindings)) => {
+                if !bindings.contains(&binding) {
+                    bindings.push(binding);
+                }
+            }
+            Some(bindings) => *bindings = ActionBindings::Button(vec![binding]),
+            None => self
+                .actions
+                .push((action, ActionBindings::Button(vec![binding]))),
+        }
+    }
+
+    /// Defines the given action as axis composed of a negative and a positive
+    /// direction, replacing all of its previous bindings.
+    pub fn bind_axis<N, P>(&mut self, action: A, negative: N, positive: P)
+    where
+        N: IntoIterator,
+        N::Item: Into<Binding>,
+        P: IntoIterator,
+        P::Item: Into<Binding>,
+    {
+        let bindings = ActionBindings::Axis {
+            negative: negative.into_iter().map(Into::into).collect(),
+            positive: positive.into_iter().map(Into::into).collect(),
+        };
+        match self.get_mut(&action) {
+            Some(previous) => *previous = bindings,
+            None => self.actions.push((action, bindings)),
+        }
+    }
+
+    /// Replaces all bindings of the given action.
+    pub fn rebind<B, I>(&mut self, action: A, bindings: I)
+    where
+        B: Into<Binding>,
+        I: IntoIterator<Item = B>,
+    {
+        let bindings = ActionBindings::Button(bindings.into_iter().map(Into::into).collect());
+        match self.get_mut(&action) {
+            Some(previous) => *previous = bindings,
+            None => self.actions.push((action, bindings)),
+        }
+    }
+
+    /// Removes a single binding from the given action.
+    pub fn unbind(&mut self, action: &A, binding: &Binding) {
+        match self.get_mut(action) {
+            Some(ActionBindings::Button(bindings)) => bindings.retain(|b| b != binding),
+            Some(ActionBindings::Axis { negative, positive }) => {
+                negative.retain(|b| b != binding);
+                positive.retain(|b| b != binding);
+            }
+            None => {}
+        }
+    }
+
+    /// Removes the given action together with all of its bindings.
+    pub fn remove(&mut self, action: &A) {
+        self.actions.retain(|(a, _)| a != action);
+    }
+
+    /// Get all bindings of the given action.
+    ///
+    /// For axis actions the negative bindings are followed by the positive
+    /// ones.
+    pub fn bindings(&self, action: &A) -> Vec<Binding> {
+        match self.get(action) {
+            Some(ActionBindings::Button(bindings)) => bindings.clone(),
+            Some(ActionBindings::Axis { negative, positive }) => {
+                negative.iter().chain(positive.iter()).copied().collect()
+            }
+            None => vec![],
+        }
+    }
+
+    /// Get all defined actions in the order they have been defined.
+    pub fn actions(&self) -> impl Iterator<Item = &A> {
+        self.actions.iter().map(|(action, _)| action)
+    }
+
+    /// Checks if any binding of the action was pressed this frame.
+    pub fn is_action_pressed<I: KeyboardState + MouseState>(&self, input: &I, action: &A) -> bool {
+        self.bindings(action).iter().any(|b| b.is_pressed(input))
+    }
+
+    /// Checks if any binding of the action is currently being held down.
+    pub fn is_action_down<I: KeyboardState + MouseState>(&self, input: &I, action: &A) -> bool {
+        self.bindings(action).iter().any(|b| b.is_down(input))
+    }
+
+    /// Checks if a binding of the action was released this frame, while no
+    /// other binding of it is held down anymore.
+    pub fn is_action_released<I: KeyboardState + MouseState>(&self, input: &I, action: &A) -> bool {
+        let bindings = self.bindings(action);
+        bindings.iter().any(|b| b.is_released(input)) && !bindings.iter().any(|b| b.is_down(input))
+    }
+
+    /// Checks if no binding of the action is currently being held down.
+    pub fn is_action_up<I: KeyboardState + MouseState>(&self, input: &I, action: &A) -> bool {
+        !self.is_action_down(input, action)
+    }
+
+    /// Get the value of an axis action.
+    ///
+    /// # Returns
+    /// * `-1.0` - If only a negative binding is held down
+    /// * `1.0` - If only a positive binding is held down
+    /// * `0.0` - If none or both directions are held down, or the action is
+    ///   not defined as axis
+    pub fn axis<I: KeyboardState + MouseState>(&self, input: &I, action: &A) -> f32 {
+        match self.get(action) {
+            Some(ActionBindings::Axis { negative, positive }) => {
+                let negative = negative.iter().any(|b| b.is_down(input));
+                let positive = positive.iter().any(|b| b.is_down(input));
+                match (negative, positive) {
+                    (true, false) => -1.0,
+                    (false, true) => 1.0,
+                    _ => 0.0,
+                }
+            }
+            _ => 0.0,
+        }
+    }
+
+    fn get(&self, action: &A) -> Option<&ActionBindings> {
+        self.actions
+            .iter()
+            .find(|(a, _)| a == action)
+            .map(|(_, bindings)| bindings)
+    }
+
+    fn get_mut(&mut self, action: &A) -> Option<&mut ActionBindings> {
+        self.actions
+            .iter_mut()
+            .find(|(a, _)| a == action)
+            .map(|(_, bindings)| bindings)
+    }
+}
+
+impl<A: PartialEq + Display> ActionMap<A> {
+    /// Serializes all bindings into the config format described in the
+    /// [module documentation](self).
+    pub fn to_config(&self) -> String {
+        fn join(bindings: &[Binding]) -> String {
+            bindings
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+
+        let mut config = String::new();
+        for (action, bindings) in self.actions.iter() {
+            let line = match bindings {
+                ActionBindings::Button(bindings) => format!("{action} = {}\n", join(bindings)),
+                ActionBindings::Axis { negative, positive } => {
+                    format!("{action} = {} / {}\n", join(negative), join(positive))
+                }
+            };
+            config.push_str(&line);
+        }
+        config
+    }
+}
+
+impl<A: PartialEq + FromStr> ActionMap<A>
+where
+    A::Err: Display,
+{
+    /// Parses bindings from the config format described in the
+    /// [module documentation](self).
+    ///
+    /// # Example
+    /// ```
+    /// use pixel_loop::input::{ActionMap, Binding, KeyboardKey, MouseButton};
+    ///
+    /// let actions = ActionMap::<String>::from_config("jump = Space, Mouse:Left\nmove_x = A / D")?;
+    /// assert_eq!(
+    ///     actions.bindings(&"jump".to_string()),
+    ///     vec![Binding::Key(KeyboardKey::Space), Binding::MouseButton(MouseButton::Left)]
+    /// );
+    /// assert_eq!(actions.to_config(), "jump = Space, Mouse:Left\nmove_x = A / D\n");
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if a line is malformed or contains unknown actions,
+    /// keys or mouse buttons.
+    pub fn from_config(config: &str) -> Result<Self> {
+        fn parse_list(list: &str) -> Result<Vec<Binding>> {
+            list.split(',')
+                .map(str::trim)
+                .filter(|binding| !binding.is_empty())
+                .map(str::parse)
+                .collect()
+        }
+
+        let mut map = Self::new();
+        for (index, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parse_line = || -> Result<(A, ActionBindings)> {
+                let (action, bindings) = line
+                    .split_once('=')
+                    .ok_or_else(|| anyhow!("expected `action = bindings`"))?;
+                let action = action
+                    .trim()
+                    .parse::<A>()
+                    .map_err(|error| anyhow!("unknown action {:?}: {error}", action.trim()))?;
+                let bindings = match bindings.split_once('/') {
+                    Some((negative, positive)) => ActionBindings::Axis {
+                        negative: parse_list(negative)?,
+                        positive: parse_list(positive)?,
+                    },
+                    None => ActionBindings::Button(parse_list(bindings)?),
+                };
+                Ok((action, bindings))
+            };
+            let (action, bindings) =
+                parse_line().with_context(|| format!("line {}: {line:?}", index + 1))?;
+
+            if map.get(&action).is_some() {
+                bail!("line {}: action defined twice {line:?}", index + 1);
+            }
+            map.actions.push((action, bindings));
+        }
+        Ok(map)
+    }
+}
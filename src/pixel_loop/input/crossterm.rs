@@ -2,30 +2,100 @@
 //!
 //! This module provides a terminal input implementation using the crossterm crate.
 //! It supports both basic and enhanced keyboard input modes depending on terminal
-//! capabilities.
+//! capabilities, as well as mouse input.
 
-use crate::NextLoopState;
+use crate::{ExitRequest, NextLoopState};
 
-use super::{InputState, KeyboardKey, KeyboardState};
+use super::{InputState, KeyboardKey, KeyboardState, MouseButton, MouseState, TextInputState};
 use anyhow::Result;
 use crossterm::event::{
-    Event, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    DisableMouseCapture, EnableMouseCapture, Event, KeyboardEnhancementFlags,
+    PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
 };
 use crossterm::execute;
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(unix)]
+use std::sync::{Arc, OnceLock};
+
+/// Whether keyboard enhancement flags have been pushed to the terminal, and
+/// therefore need to be popped when restoring it.
+pub(crate) static KEYBOARD_ENHANCEMENT_PUSHED: AtomicBool = AtomicBool::new(false);
+
+/// Signals handled as requests to exit the loop.
+#[cfg(unix)]
+const EXIT_SIGNALS: [(i32, ExitRequest); 3] = [
+    (signal_hook::consts::SIGINT, ExitRequest::Interrupt),
+    (signal_hook::consts::SIGTERM, ExitRequest::Terminate),
+    (signal_hook::consts::SIGHUP, ExitRequest::Hangup),
+];
+
+/// Whether the default behaviour of the exit signals is emulated, as no loop
+/// handles them.
+///
+/// Unregistering the handlers of a loop does not restore the default
+/// disposition of a signal, as signal-hook keeps its own handler installed.
+/// Therefore an emulation of the default behaviour is registered once, which
+/// is enabled whenever no loop handles the signals.
+#[cfg(unix)]
+static DEFAULT_SIGNAL_BEHAVIOUR: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+/// Signal handler registered while the loop is running.
+#[cfg(unix)]
+struct SignalHandler {
+    request: ExitRequest,
+    received: Arc<AtomicBool>,
+    ids: Vec<signal_hook::SigId>,
+}
 
 /// Input state handler for terminal input using crossterm.
 ///
 /// This implementation supports both basic and enhanced keyboard modes:
-/// - Enhanced mode provides accurate key press/release events if supported by the terminal
+/// - Enhanced mode provides accurate key press/release/repeat events if supported by the terminal
 /// - Basic mode falls back to simulated key releases based on timing
+///
+/// Modifier keys (Shift, Control, Alt and Super) are reported as
+/// [KeyboardKey]s as well. In enhanced mode they are tracked individually. In
+/// basic mode terminals do not report modifier keys on their own. Instead they
+/// are derived from the modifiers of other key and mouse events, and always
+/// reported as their left variant (eg. [KeyboardKey::LeftShift]). Similarly
+/// key repeats can not be distinguished from quickly pressing a key again in
+/// basic mode.
+///
+/// Mouse positions are reported in pixels of the
+/// [CrosstermCanvas](crate::canvas::CrosstermCanvas). As every terminal cell
+/// represents multiple pixels, depending on the
+/// [TerminalEncoding](crate::canvas::crossterm::TerminalEncoding) of the
+/// canvas, the upper left pixel of a cell is reported.
+///
+/// Pressing Ctrl-C, as well as receiving SIGINT, SIGTERM or SIGHUP on unix
+/// platforms, is reported as [ExitRequest]. Unless the game decides otherwise
+/// (See [on_exit_request](crate::Game::on_exit_request)), the loop is exited
+/// and the terminal is restored properly. If a signal is received again
+/// before the loop could handle the first one, the process is terminated
+/// immediately. After the loop has been finished, the signals terminate the
+/// process again.
 pub struct CrosstermInputState {
     event_queue: Vec<Event>,
     keys_down: HashMap<KeyboardKey, usize>,
     keys_pressed_this_update: HashSet<KeyboardKey>,
     keys_released_this_update: HashSet<KeyboardKey>,
+    keys_repeated_this_update: HashSet<KeyboardKey>,
+    text_this_update: String,
     event_cycles_before_released: usize,
     enhanced_keyboard: bool,
+    mouse_position: Option<(u32, u32)>,
+    cell_size: (u32, u32),
+    mouse_buttons_down: HashSet<MouseButton>,
+    mouse_buttons_pressed_this_update: HashSet<MouseButton>,
+    mouse_buttons_released_this_update: HashSet<MouseButton>,
+    mouse_wheel_delta: (f32, f32),
+    ctrl_c_handling: bool,
+    signal_handling: bool,
+    exit_request: Option<ExitRequest>,
+    polled: bool,
+    #[cfg(unix)]
+    signal_handlers: Vec<SignalHandler>,
 }
 
 impl Default for CrosstermInputState {
@@ -49,8 +119,22 @@ impl CrosstermInputState {
             keys_down: HashMap::new(),
             keys_pressed_this_update: HashSet::new(),
             keys_released_this_update: HashSet::new(),
+            keys_repeated_this_update: HashSet::new(),
+            text_this_update: String::new(),
             event_cycles_before_released: 2,
             enhanced_keyboard: false,
+            mouse_position: None,
+            cell_size: (1, 2),
+            mouse_buttons_down: HashSet::new(),
+            mouse_buttons_pressed_this_update: HashSet::new(),
+            mouse_buttons_released_this_update: HashSet::new(),
+            mouse_wheel_delta: (0.0, 0.0),
+            ctrl_c_handling: true,
+            signal_handling: true,
+            exit_request: None,
+            polled: false,
+            #[cfg(unix)]
+            signal_handlers: vec![],
         }
     }
 
@@ -79,10 +163,100 @@ impl CrosstermInputState {
             ..self
         }
     }
+
+    /// Enables or disables reporting Ctrl-C as [ExitRequest::Interrupt].
+    ///
+    /// If disabled, Ctrl-C is reported as any other key combination. It is
+    /// enabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use pixel_loop::input::CrosstermInputState;
+    ///
+    /// // Ctrl-C copies within this editor
+    /// let input_state = CrosstermInputState::new()
+    ///     .with_ctrl_c_handling(false);
+    /// ```
+    pub fn with_ctrl_c_handling(self, enabled: bool) -> Self {
+        Self {
+            ctrl_c_handling: enabled,
+            ..self
+        }
+    }
+
+    /// Enables or disables reporting SIGINT, SIGTERM and SIGHUP as
+    /// [ExitRequest]s on unix platforms.
+    ///
+    /// If disabled, the default behaviour of the signals applies, which
+    /// terminates the process without restoring the terminal. It is enabled
+    /// by default. Once the loop is finished, the default behaviour applies
+    /// again.
+    pub fn with_signal_handling(self, enabled: bool) -> Self {
+        Self {
+            signal_handling: enabled,
+            ..self
+        }
+    }
+
+    /// Registers the emulation of the default behaviour of the exit signals,
+    /// unless this has been done before.
+    #[cfg(unix)]
+    fn default_signal_behaviour() -> Result<&'static Arc<AtomicBool>> {
+        if let Some(enabled) = DEFAULT_SIGNAL_BEHAVIOUR.get() {
+            return Ok(enabled);
+        }
+        let enabled = Arc::new(AtomicBool::new(false));
+        for (signal, _) in EXIT_SIGNALS {
+            signal_hook::flag::register_conditional_default(signal, enabled.clone())?;
+        }
+        Ok(DEFAULT_SIGNAL_BEHAVIOUR.get_or_init(|| enabled))
+    }
+
+    #[cfg(unix)]
+    fn register_signal_handlers(&mut self) -> Result<()> {
+        // Registered first, to run before the handlers of the loop.
+        Self::default_signal_behaviour()?.store(false, Ordering::SeqCst);
+        for (signal, request) in EXIT_SIGNALS {
+            let received = Arc::new(AtomicBool::new(false));
+            // The conditional shutdown needs to be registered first, to only
+            // terminate if an earlier signal is still pending.
+            let ids = vec![
+                signal_hook::flag::register_conditional_shutdown(
+                    signal,
+                    request.code(),
+                    received.clone(),
+                )?,
+                signal_hook::flag::register(signal, received.clone())?,
+            ];
+            self.signal_handlers.push(SignalHandler {
+                request,
+                received,
+                ids,
+            });
+        }
+        Ok(())
+    }
+
+    /// Unregisters the signal handlers of the loop and restores the default
+    /// behaviour of the signals.
+    #[cfg(unix)]
+    fn unregister_signal_handlers(&mut self) {
+        if self.signal_handlers.is_empty() {
+            return;
+        }
+        for handler in self.signal_handlers.drain(..) {
+            for id in handler.ids {
+                signal_hook::low_level::unregister(id);
+            }
+        }
+        if let Some(enabled) = DEFAULT_SIGNAL_BEHAVIOUR.get() {
+            enabled.store(true, Ordering::SeqCst);
+        }
+    }
 }
 
 fn map_crossterm_keycode_to_pixel_loop(keycode: &crossterm::event::KeyCode) -> Option<KeyboardKey> {
-    use crossterm::event::KeyCode;
+    use crossterm::event::{KeyCode, ModifierKeyCode};
     match keycode {
         KeyCode::Backspace => Some(KeyboardKey::Backspace),
         KeyCode::Enter => Some(KeyboardKey::Enter),
@@ -174,7 +348,46 @@ fn map_crossterm_keycode_to_pixel_loop(keycode: &crossterm::event::KeyCode) -> O
         KeyCode::Menu => Some(KeyboardKey::KbMenu),
         KeyCode::KeypadBegin => None,
         KeyCode::Media(_) => None,
-        KeyCode::Modifier(_) => None, //@TODO: implement
+        KeyCode::Modifier(ref modifier) => match modifier {
+            ModifierKeyCode::LeftShift => Some(KeyboardKey::LeftShift),
+            ModifierKeyCode::LeftControl => Some(KeyboardKey::LeftControl),
+            ModifierKeyCode::LeftAlt => Some(KeyboardKey::LeftAlt),
+            ModifierKeyCode::LeftSuper => Some(KeyboardKey::LeftSuper),
+            ModifierKeyCode::RightShift => Some(KeyboardKey::RightShift),
+            ModifierKeyCode::RightControl => Some(KeyboardKey::RightControl),
+            ModifierKeyCode::RightAlt => Some(KeyboardKey::RightAlt),
+            ModifierKeyCode::RightSuper => Some(KeyboardKey::RightSuper),
+            _ => None,
+        },
+    }
+}
+
+/// Map the active modifiers of an event to the (left) modifier keys.
+///
+/// Used in basic keyboard mode, where modifier keys are not reported on their
+/// own.
+fn map_crossterm_modifiers_to_pixel_loop(
+    modifiers: &crossterm::event::KeyModifiers,
+) -> Vec<KeyboardKey> {
+    use crossterm::event::KeyModifiers;
+    [
+        (KeyModifiers::SHIFT, KeyboardKey::LeftShift),
+        (KeyModifiers::CONTROL, KeyboardKey::LeftControl),
+        (KeyModifiers::ALT, KeyboardKey::LeftAlt),
+        (KeyModifiers::SUPER, KeyboardKey::LeftSuper),
+    ]
+    .into_iter()
+    .filter(|(modifier, _)| modifiers.contains(*modifier))
+    .map(|(_, key)| key)
+    .collect()
+}
+
+fn map_crossterm_mouse_button_to_pixel_loop(button: &crossterm::event::MouseButton) -> MouseButton {
+    use crossterm::event::MouseButton as CrosstermMouseButton;
+    match button {
+        CrosstermMouseButton::Left => MouseButton::Left,
+        CrosstermMouseButton::Right => MouseButton::Right,
+        CrosstermMouseButton::Middle => MouseButton::Middle,
     }
 }
 
@@ -208,16 +421,140 @@ impl CrosstermInputState {
         self.event_queue.push(event);
     }
 
+    /// Sets the number of pixels (columns, rows) represented by a terminal
+    /// cell, which is used to map mouse positions to pixels.
+    pub(crate) fn set_cell_size(&mut self, cell_size: (u32, u32)) {
+        self.cell_size = cell_size;
+    }
+
     fn take_all_queued_events(&mut self) -> Vec<Event> {
         self.event_queue.drain(..).collect()
     }
 
-    fn next_loop_fallback(&mut self, next_events: Vec<Event>) -> Result<()> {
-        use crossterm::event::{KeyEvent, KeyEventKind};
+    /// Resets the state, which is only reported for a single update cycle.
+    fn clear_this_update(&mut self) {
+        self.keys_pressed_this_update.clear();
+        self.keys_released_this_update.clear();
+        self.keys_repeated_this_update.clear();
+        self.text_this_update.clear();
+        self.mouse_buttons_pressed_this_update.clear();
+        self.mouse_buttons_released_this_update.clear();
+        self.mouse_wheel_delta = (0.0, 0.0);
+    }
+
+    /// Processes all queued events and received signals.
+    ///
+    /// The state of the last update cycle is reset first, unless events have
+    /// been polled for the upcoming cycle already. Only update cycles count
+    /// towards the simulated key releases of the basic keyboard mode.
+    fn process_events(&mut self, update: bool) -> Result<NextLoopState> {
+        use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+        if !self.polled {
+            self.clear_this_update();
+        }
+        self.polled = !update;
+
+        let next_events = self.take_all_queued_events();
+        for event in next_events.iter() {
+            match event {
+                // Handle Ctrl-C
+                Event::Key(KeyEvent {
+                    kind: KeyEventKind::Press,
+                    code: KeyCode::Char('c') | KeyCode::Char('C'),
+                    modifiers: KeyModifiers::CONTROL,
+                    ..
+                }) if self.ctrl_c_handling => {
+                    self.exit_request = Some(ExitRequest::Interrupt);
+                }
+                _ => {}
+            }
+        }
+
+        #[cfg(unix)]
+        for handler in self.signal_handlers.iter() {
+            if handler.received.swap(false, Ordering::SeqCst) {
+                self.exit_request = Some(handler.request);
+            }
+        }
+
+        self.next_loop_mouse(&next_events);
+        self.next_loop_text(&next_events);
+
+        if self.enhanced_keyboard {
+            self.next_loop_enhanced(next_events)?;
+        } else {
+            self.next_loop_fallback(next_events, update)?;
+        }
+
+        Ok(NextLoopState::Continue)
+    }
 
-        let removed_keys_down = decrement_key_ref_counts(&mut self.keys_down);
-        let keys_pressed_last_update = std::mem::take(&mut self.keys_pressed_this_update);
-        let keys_released_last_update = std::mem::take(&mut self.keys_released_this_update);
+    fn next_loop_mouse(&mut self, next_events: &[Event]) {
+        use crossterm::event::{MouseEvent, MouseEventKind};
+
+        for event in next_events {
+            if let Event::Mouse(MouseEvent {
+                kind, column, row, ..
+            }) = event
+            {
+                self.mouse_position = Some((
+                    *column as u32 * self.cell_size.0,
+                    *row as u32 * self.cell_size.1,
+                ));
+                match kind {
+                    MouseEventKind::Down(button) => {
+                        let button = map_crossterm_mouse_button_to_pixel_loop(button);
+                        if self.mouse_buttons_down.insert(button) {
+                            self.mouse_buttons_pressed_this_update.insert(button);
+                        }
+                    }
+                    MouseEventKind::Up(button) => {
+                        let button = map_crossterm_mouse_button_to_pixel_loop(button);
+                        if self.mouse_buttons_down.remove(&button) {
+                            self.mouse_buttons_released_this_update.insert(button);
+                        }
+                    }
+                    MouseEventKind::ScrollUp => self.mouse_wheel_delta.1 += 1.0,
+                    MouseEventKind::ScrollDown => self.mouse_wheel_delta.1 -= 1.0,
+                    MouseEventKind::ScrollLeft => self.mouse_wheel_delta.0 -= 1.0,
+                    MouseEventKind::ScrollRight => self.mouse_wheel_delta.0 += 1.0,
+                    MouseEventKind::Drag(_) | MouseEventKind::Moved => {}
+                }
+            }
+        }
+    }
+
+    fn next_loop_text(&mut self, next_events: &[Event]) {
+        use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+        for event in next_events {
+            if let Event::Key(KeyEvent {
+                kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                code: KeyCode::Char(character),
+                modifiers,
+                ..
+            }) = event
+            {
+                // Characters typed with control, alt or super are shortcuts
+                // not text.
+                let shortcut_modifiers =
+                    KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SUPER;
+                if !modifiers.intersects(shortcut_modifiers) && !character.is_control() {
+                    self.text_this_update.push(*character);
+                }
+            }
+        }
+    }
+
+    fn next_loop_fallback(&mut self, next_events: Vec<Event>, update: bool) -> Result<()> {
+        use crossterm::event::{KeyEvent, KeyEventKind, MouseEvent};
+
+        let removed_keys_down = if update {
+            decrement_key_ref_counts(&mut self.keys_down)
+        } else {
+            vec![]
+        };
 
         for event in next_events {
             match event {
@@ -225,23 +562,29 @@ impl CrosstermInputState {
                 Event::Key(KeyEvent {
                     kind: KeyEventKind::Press,
                     ref code,
+                    ref modifiers,
                     ..
                 }) => {
+                    for modifier_key in map_crossterm_modifiers_to_pixel_loop(modifiers) {
+                        self.refresh_key_fallback(modifier_key);
+                    }
+
                     if let Some(keyboard_key) = map_crossterm_keycode_to_pixel_loop(code) {
                         // eprintln!("key DOWN handled {:?}", keyboard_key);
-                        if self
-                            .keys_down
-                            .insert(keyboard_key, self.event_cycles_before_released)
-                            .is_none()
-                        {
-                            // eprintln!("key PRESS handled {:?}", keyboard_key);
-                            // Key is newly inserted.
-                            self.keys_pressed_this_update.insert(keyboard_key);
+                        if !self.refresh_key_fallback(keyboard_key) {
+                            // Basic terminals report repeats as presses
+                            self.keys_repeated_this_update.insert(keyboard_key);
                         }
                     } else {
                         // eprintln!("Keypress NOT mapped");
                     }
                 }
+                // Modifiers held while using the mouse
+                Event::Mouse(MouseEvent { ref modifiers, .. }) => {
+                    for modifier_key in map_crossterm_modifiers_to_pixel_loop(modifiers) {
+                        self.refresh_key_fallback(modifier_key);
+                    }
+                }
                 _ => {}
             }
         }
@@ -257,12 +600,27 @@ impl CrosstermInputState {
         Ok(())
     }
 
+    /// Marks the given key as held down in basic keyboard mode, resetting
+    /// the number of cycles until it is considered released.
+    ///
+    /// Returns whether the key has been newly pressed.
+    fn refresh_key_fallback(&mut self, keyboard_key: KeyboardKey) -> bool {
+        if self
+            .keys_down
+            .insert(keyboard_key, self.event_cycles_before_released)
+            .is_none()
+        {
+            // eprintln!("key PRESS handled {:?}", keyboard_key);
+            // Key is newly inserted.
+            self.keys_pressed_this_update.insert(keyboard_key);
+            return true;
+        }
+        false
+    }
+
     fn next_loop_enhanced(&mut self, next_events: Vec<Event>) -> Result<()> {
         use crossterm::event::{KeyEvent, KeyEventKind};
 
-        self.keys_pressed_this_update.drain();
-        self.keys_released_this_update.drain();
-
         for event in next_events {
             match event {
                 // Handle all pressed keys
@@ -291,7 +649,16 @@ impl CrosstermInputState {
                             }
                             KeyEventKind::Repeat => {
                                 // eprintln!("KEY REPEAT: {:?}", keyboard_key);
-                                // @TODO: Not handled yet. There isn't an API in hour trait for that (yet)
+                                if self
+                                    .keys_down
+                                    .insert(keyboard_key, self.event_cycles_before_released)
+                                    .is_none()
+                                {
+                                    // The initial press has been missed
+                                    self.keys_pressed_this_update.insert(keyboard_key);
+                                } else {
+                                    self.keys_repeated_this_update.insert(keyboard_key);
+                                }
                             }
                         }
                     }
@@ -304,25 +671,51 @@ impl CrosstermInputState {
     }
 }
 
+impl AsMut<CrosstermInputState> for CrosstermInputState {
+    fn as_mut(&mut self) -> &mut CrosstermInputState {
+        self
+    }
+}
+
 impl InputState for CrosstermInputState {
     fn begin(&mut self) -> Result<()> {
         crossterm::terminal::enable_raw_mode()?;
         if crossterm::terminal::supports_keyboard_enhancement()? {
             // eprintln!("Enhanced Terminal YEAH!");
             self.enhanced_keyboard = true;
+            // All keys are requested as escape codes, as otherwise modifier
+            // keys are not reported on their own. Alternate keys are needed
+            // to receive the shifted characters according to the keyboard
+            // layout for text input.
             execute!(
                 std::io::stdout(),
-                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+                PushKeyboardEnhancementFlags(
+                    KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+                        | KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                        | KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES
+                        | KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS
+                )
             )?;
+            KEYBOARD_ENHANCEMENT_PUSHED.store(true, Ordering::SeqCst);
         } else {
             // eprintln!("No enhanced Terminal :_(");
         }
+        execute!(std::io::stdout(), EnableMouseCapture)?;
+        #[cfg(unix)]
+        if self.signal_handling {
+            self.register_signal_handlers()?;
+        }
         Ok(())
     }
 
     fn finish(&mut self) -> Result<()> {
+        #[cfg(unix)]
+        self.unregister_signal_handlers();
+        execute!(std::io::stdout(), DisableMouseCapture)?;
         if self.enhanced_keyboard {
-            execute!(std::io::stdout(), PopKeyboardEnhancementFlags)?;
+            if KEYBOARD_ENHANCEMENT_PUSHED.swap(false, Ordering::SeqCst) {
+                execute!(std::io::stdout(), PopKeyboardEnhancementFlags)?;
+            }
             self.enhanced_keyboard = false;
         }
         crossterm::terminal::disable_raw_mode()?;
@@ -330,32 +723,15 @@ impl InputState for CrosstermInputState {
     }
 
     fn next_loop(&mut self) -> Result<NextLoopState> {
-        use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
-
-        let next_events = self.take_all_queued_events();
-        for event in next_events.iter() {
-            match event {
-                // Handle Ctrl-C
-                Event::Key(KeyEvent {
-                    kind: KeyEventKind::Press,
-                    code: KeyCode::Char('c') | KeyCode::Char('C'),
-                    modifiers: KeyModifiers::CONTROL,
-                    ..
-                }) => {
-                    // SIGINT exitcode
-                    return Ok(NextLoopState::Exit(130));
-                }
-                _ => {}
-            }
-        }
+        self.process_events(true)
+    }
 
-        if self.enhanced_keyboard {
-            self.next_loop_enhanced(next_events)?;
-        } else {
-            self.next_loop_fallback(next_events)?;
-        }
+    fn poll(&mut self) -> Result<NextLoopState> {
+        self.process_events(false)
+    }
 
-        Ok(NextLoopState::Continue)
+    fn take_exit_request(&mut self) -> Option<ExitRequest> {
+        self.exit_request.take()
     }
 }
 
@@ -375,4 +751,36 @@ impl KeyboardState for CrosstermInputState {
     fn is_key_up(&self, key: KeyboardKey) -> bool {
         !self.keys_down.contains_key(&key)
     }
+
+    fn is_key_repeated(&self, key: KeyboardKey) -> bool {
+        self.keys_repeated_this_update.contains(&key)
+    }
+}
+
+impl MouseState for CrosstermInputState {
+    fn mouse_position(&self) -> Option<(u32, u32)> {
+        self.mouse_position
+    }
+
+    fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_pressed_this_update.contains(&button)
+    }
+
+    fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_down.contains(&button)
+    }
+
+    fn is_mouse_button_released(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_released_this_update.contains(&button)
+    }
+
+    fn mouse_wheel_delta(&self) -> (f32, f32) {
+        self.mouse_wheel_delta
+    }
+}
+
+impl TextInputState for CrosstermInputState {
+    fn text_input(&self) -> &str {
+        &self.text_this_update
+    }
 }
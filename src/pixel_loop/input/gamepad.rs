@@ -0,0 +1,199 @@
+//! Virtual gamepads not backed by any real device.
+//!
+//! This module provides a [GamepadState] implementation, whose gamepads are
+//! controlled programmatically. It allows to test gamepad handling without any
+//! device connected, and is the foundation of the gamepad support of the
+//! [HeadlessInputState](super::HeadlessInputState) and the device backed
+//! implementations.
+
+use super::{
+    apply_deadzone, GamepadButton, GamepadId, GamepadState, GamepadStick, GamepadTrigger,
+    DEFAULT_GAMEPAD_DEADZONE,
+};
+use std::collections::{BTreeMap, HashSet};
+
+/// A single change of the state of a gamepad.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GamepadInput {
+    /// The gamepad is connected
+    Connect,
+    /// The gamepad is disconnected, releasing all of its buttons
+    Disconnect,
+    /// The given button is pressed down
+    ButtonPress(GamepadButton),
+    /// The given button is released
+    ButtonRelease(GamepadButton),
+    /// The given analog stick is moved to the given position (x, y)
+    Stick(GamepadStick, f32, f32),
+    /// The given analog trigger is pulled to the given value
+    Trigger(GamepadTrigger, f32),
+}
+
+/// State of a single connected gamepad.
+#[derive(Debug, Clone, Default)]
+struct Gamepad {
+    buttons_down: HashSet<GamepadButton>,
+    buttons_pressed_this_update: HashSet<GamepadButton>,
+    buttons_released_this_update: HashSet<GamepadButton>,
+    left_stick: (f32, f32),
+    right_stick: (f32, f32),
+    left_trigger: f32,
+    right_trigger: f32,
+}
+
+/// Gamepad state handler for virtual gamepads.
+///
+/// Changes are applied immediately using [apply](VirtualGamepadState::apply).
+/// Pressed and released buttons are reported until the next call to
+/// [next_update](VirtualGamepadState::next_update).
+///
+/// Applying any input to a gamepad, which is not connected, connects it
+/// implicitly.
+///
+/// # Example
+/// ```
+/// use pixel_loop::input::gamepad::GamepadInput;
+/// use pixel_loop::input::{GamepadButton, GamepadId, GamepadState, GamepadStick, VirtualGamepadState};
+///
+/// let pad = GamepadId(0);
+/// let mut gamepads = VirtualGamepadState::new().with_deadzone(0.2);
+/// gamepads.apply(pad, GamepadInput::ButtonPress(GamepadButton::South));
+/// gamepads.apply(pad, GamepadInput::Stick(GamepadStick::Left, 0.1, 0.0));
+///
+/// assert_eq!(gamepads.gamepads(), vec![pad]);
+/// assert!(gamepads.is_gamepad_button_pressed(pad, GamepadButton::South));
+/// assert_eq!(gamepads.gamepad_stick(pad, GamepadStick::Left), (0.0, 0.0));
+///
+/// gamepads.next_update();
+/// assert!(!gamepads.is_gamepad_button_pressed(pad, GamepadButton::South));
+/// assert!(gamepads.is_gamepad_button_down(pad, GamepadButton::South));
+/// ```
+#[derive(Debug, Clone)]
+pub struct VirtualGamepadState {
+    gamepads: BTreeMap<GamepadId, Gamepad>,
+    deadzone: f32,
+}
+
+impl Default for VirtualGamepadState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VirtualGamepadState {
+    /// Creates a new VirtualGamepadState without any connected gamepads.
+    pub fn new() -> Self {
+        Self {
+            gamepads: BTreeMap::new(),
+            deadzone: DEFAULT_GAMEPAD_DEADZONE,
+        }
+    }
+
+    /// Sets the deadzone applied to analog sticks (See [apply_deadzone]).
+    ///
+    /// Defaults to [DEFAULT_GAMEPAD_DEADZONE].
+    pub fn with_deadzone(mut self, deadzone: f32) -> Self {
+        self.deadzone = deadzone;
+        self
+    }
+
+    /// Get the deadzone applied to analog sticks.
+    pub fn deadzone(&self) -> f32 {
+        self.deadzone
+    }
+
+    /// Applies a change to the state of the given gamepad.
+    pub fn apply(&mut self, gamepad: GamepadId, input: GamepadInput) {
+        if input == GamepadInput::Disconnect {
+            self.gamepads.remove(&gamepad);
+            return;
+        }
+
+        let state = self.gamepads.entry(gamepad).or_default();
+        match input {
+            GamepadInput::Connect | GamepadInput::Disconnect => {}
+            GamepadInput::ButtonPress(button) => {
+                if state.buttons_down.insert(button) {
+                    state.buttons_pressed_this_update.insert(button);
+                }
+            }
+            GamepadInput::ButtonRelease(button) => {
+                if state.buttons_down.remove(&button) {
+                    state.buttons_released_this_update.insert(button);
+                }
+            }
+            GamepadInput::Stick(GamepadStick::Left, x, y) => {
+                state.left_stick = (x.clamp(-1.0, 1.0), y.clamp(-1.0, 1.0));
+            }
+            GamepadInput::Stick(GamepadStick::Right, x, y) => {
+                state.right_stick = (x.clamp(-1.0, 1.0), y.clamp(-1.0, 1.0));
+            }
+            GamepadInput::Trigger(GamepadTrigger::Left, value) => {
+                state.left_trigger = value.clamp(0.0, 1.0);
+            }
+            GamepadInput::Trigger(GamepadTrigger::Right, value) => {
+                state.right_trigger = value.clamp(0.0, 1.0);
+            }
+        }
+    }
+
+    /// Forgets about buttons pressed and released during the last update
+    /// cycle.
+    ///
+    /// This needs to be called once at the beginning of every update cycle.
+    pub fn next_update(&mut self) {
+        for state in self.gamepads.values_mut() {
+            state.buttons_pressed_this_update.clear();
+            state.buttons_released_this_update.clear();
+        }
+    }
+}
+
+impl GamepadState for VirtualGamepadState {
+    fn gamepads(&self) -> Vec<GamepadId> {
+        self.gamepads.keys().copied().collect()
+    }
+
+    fn is_gamepad_connected(&self, gamepad: GamepadId) -> bool {
+        self.gamepads.contains_key(&gamepad)
+    }
+
+    fn is_gamepad_button_pressed(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads
+            .get(&gamepad)
+            .is_some_and(|state| state.buttons_pressed_this_update.contains(&button))
+    }
+
+    fn is_gamepad_button_down(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads
+            .get(&gamepad)
+            .is_some_and(|state| state.buttons_down.contains(&button))
+    }
+
+    fn is_gamepad_button_released(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads
+            .get(&gamepad)
+            .is_some_and(|state| state.buttons_released_this_update.contains(&button))
+    }
+
+    fn gamepad_stick(&self, gamepad: GamepadId, stick: GamepadStick) -> (f32, f32) {
+        match self.gamepads.get(&gamepad) {
+            Some(state) => {
+                let position = match stick {
+                    GamepadStick::Left => state.left_stick,
+                    GamepadStick::Right => state.right_stick,
+                };
+                apply_deadzone(position, self.deadzone)
+            }
+            None => (0.0, 0.0),
+        }
+    }
+
+    fn gamepad_trigger(&self, gamepad: GamepadId, trigger: GamepadTrigger) -> f32 {
+        match (self.gamepads.get(&gamepad), trigger) {
+            (Some(state), GamepadTrigger::Left) => state.left_trigger,
+            (Some(state), GamepadTrigger::Right) => state.right_trigger,
+            (None, _) => 0.0,
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! Gamepad input from real devices using gilrs.
+//!
+//! This module provides a [GamepadState] implementation reading gamepads
+//! connected to the system. It is used by the
+//! [PixelsInputState](super::PixelsInputState), if the `gamepad` feature is
+//! enabled, but can be combined with any other input implementation as well.
+
+use super::gamepad::GamepadInput;
+use super::{
+    GamepadButton, GamepadId, GamepadState, GamepadStick, GamepadTrigger, VirtualGamepadState,
+};
+use anyhow::{anyhow, Result};
+use gilrs::{Axis, Button, EventType, Gilrs};
+
+// Map gilrs buttons to our GamepadButton enum
+fn map_gilrs_button_to_pixel_loop(button: Button) -> Option<GamepadButton> {
+    match button {
+        Button::South => Some(GamepadButton::South),
+        Button::East => Some(GamepadButton::East),
+        Button::North => Some(GamepadButton::North),
+        Button::West => Some(GamepadButton::West),
+        Button::LeftTrigger => Some(GamepadButton::LeftBumper),
+        Button::RightTrigger => Some(GamepadButton::RightBumper),
+        Button::Select => Some(GamepadButton::Select),
+        Button::Start => Some(GamepadButton::Start),
+        Button::Mode => Some(GamepadButton::Mode),
+        Button::LeftThumb => Some(GamepadButton::LeftStick),
+        Button::RightThumb => Some(GamepadButton::RightStick),
+        Button::DPadUp => Some(GamepadButton::DPadUp),
+        Button::DPadDown => Some(GamepadButton::DPadDown),
+        Button::DPadLeft => Some(GamepadButton::DPadLeft),
+        Button::DPadRight => Some(GamepadButton::DPadRight),
+
+        // Buttons we don't map
+        _ => None,
+    }
+}
+
+/// Gamepad state handler for gamepads connected to the system.
+pub struct GilrsGamepadState {
+    gilrs: Gilrs,
+    state: VirtualGamepadState,
+}
+
+impl GilrsGamepadState {
+    /// Creates a new GilrsGamepadState tracking all connected gamepads.
+    ///
+    /// On platforms not supported by gilrs no gamepads are reported.
+    ///
+    /// # Errors
+    /// Returns an error if the gamepad subsystem of the platform could not be
+    /// initialized.
+    pub fn new() -> Result<Self> {
+        let gilrs = match Gilrs::new() {
+            Ok(gilrs) => gilrs,
+            Err(gilrs::Error::NotImplemented(dummy)) => dummy,
+            Err(gilrs::Error::Other(error)) => {
+                return Err(anyhow!(error).context("initialize gamepad support"))
+            }
+            Err(error) => return Err(anyhow!("initialize gamepad support: {error}")),
+        };
+
+        let mut state = VirtualGamepadState::new();
+        for (id, _) in gilrs.gamepads() {
+            state.apply(GamepadId(id.into()), GamepadInput::Connect);
+        }
+
+        Ok(Self { gilrs, state })
+    }
+
+    /// Sets the deadzone applied to analog sticks (See
+    /// [apply_deadzone](super::apply_deadzone)).
+    pub fn with_deadzone(mut self, deadzone: f32) -> Self {
+        self.state = self.state.with_deadzone(deadzone);
+        self
+    }
+
+    /// Processes all gamepad events, which occurred since the last update
+    /// cycle.
+    ///
+    /// This needs to be called once at the beginning of every update cycle.
+    pub fn next_update(&mut self) {
+        self.state.next_update();
+
+        while let Some(gilrs::Event { id, event, .. }) = self.gilrs.next_event() {
+            let gamepad = GamepadId(id.into());
+            let input = match event {
+                EventType::Connected => Some(GamepadInput::Connect),
+                EventType::Disconnected => Some(GamepadInput::Disconnect),
+                EventType::ButtonPressed(button, _) => {
+                    map_gilrs_button_to_pixel_loop(button).map(GamepadInput::ButtonPress)
+                }
+                EventType::ButtonReleased(button, _) => {
+                    map_gilrs_button_to_pixel_loop(button).map(GamepadInput::ButtonRelease)
+                }
+                EventType::ButtonChanged(Button::LeftTrigger2, value, _) => {
+                    Some(GamepadInput::Trigger(GamepadTrigger::Left, value))
+                }
+                EventType::ButtonChanged(Button::RightTrigger2, value, _) => {
+                    Some(GamepadInput::Trigger(GamepadTrigger::Right, value))
+                }
+                EventType::AxisChanged(
+                    axis @ (Axis::LeftStickX
+                    | Axis::LeftStickY
+                    | Axis::RightStickX
+                    | Axis::RightStickY),
+                    _,
+                    _,
+                ) => {
+                    // Sticks are reported one axis at a time, therefore the
+                    // current position is read as a whole.
+                    let (stick, x_axis, y_axis) = match axis {
+                        Axis::LeftStickX | Axis::LeftStickY => {
+                            (GamepadStick::Left, Axis::LeftStickX, Axis::LeftStickY)
+                        }
+                        _ => (GamepadStick::Right, Axis::RightStickX, Axis::RightStickY),
+                    };
+                    let pad = self.gilrs.gamepad(id);
+                    Some(GamepadInput::Stick(
+                        stick,
+                        pad.value(x_axis),
+                        pad.value(y_axis),
+                    ))
+                }
+                _ => None,
+            };
+
+            if let Some(input) = input {
+                self.state.apply(gamepad, input);
+            }
+        }
+    }
+}
+
+impl GamepadState for GilrsGamepadState {
+    fn gamepads(&self) -> Vec<GamepadId> {
+        self.state.gamepads()
+    }
+
+    fn is_gamepad_connected(&self, gamepad: GamepadId) -> bool {
+        self.state.is_gamepad_connected(gamepad)
+    }
+
+    fn is_gamepad_button_pressed(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.state.is_gamepad_button_pressed(gamepad, button)
+    }
+
+    fn is_gamepad_button_down(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.state.is_gamepad_button_down(gamepad, button)
+    }
+
+    fn is_gamepad_button_released(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.state.is_gamepad_button_released(gamepad, button)
+    }
+
+    fn gamepad_stick(&self, gamepad: GamepadId, stick: GamepadStick) -> (f32, f32) {
+        self.state.gamepad_stick(gamepad, stick)
+    }
+
+    fn gamepad_trigger(&self, gamepad: GamepadId, trigger: GamepadTrigger) -> f32 {
+        self.state.gamepad_trigger(gamepad, trigger)
+    }
+}
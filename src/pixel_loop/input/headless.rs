@@ -0,0 +1,445 @@
+//! Scripted input handling for headless execution.
+//!
+//! This module provides an input implementation, which does not read from any
+//! real device. Instead key presses and releases, mouse movements, clicks and
+//! scrolling, as well as gamepad input, are scripted upfront for specific
+//! update cycles. In combination with the
+//! [HeadlessCanvas](crate::canvas::HeadlessCanvas) this allows to drive a game
+//! loop deterministically, eg. within tests or CI.
+
+use crate::{ExitRequest, NextLoopState};
+
+use super::gamepad::GamepadInput;
+use super::{
+    GamepadButton, GamepadId, GamepadState, GamepadStick, GamepadTrigger, InputState, KeyboardKey,
+    KeyboardState, MouseButton, MouseState, TextInputState, VirtualGamepadState,
+};
+use anyhow::Result;
+use std::collections::{BTreeMap, HashSet};
+
+/// A single scripted input event.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScriptedInput {
+    /// The given key is pressed down
+    KeyPress(KeyboardKey),
+    /// The given key is released
+    KeyRelease(KeyboardKey),
+    /// The given key, which is held down, is repeated
+    KeyRepeat(KeyboardKey),
+    /// The given character is typed
+    Character(char),
+    /// The mouse cursor is moved to the given position on the canvas, or off
+    /// the canvas if `None`
+    MouseMove(Option<(u32, u32)>),
+    /// The given mouse button is pressed down
+    MouseButtonPress(MouseButton),
+    /// The given mouse button is released
+    MouseButtonRelease(MouseButton),
+    /// The mouse wheel is scrolled by the given number of lines (x, y)
+    MouseWheel(f32, f32),
+    /// The state of the given gamepad changes
+    Gamepad(GamepadId, GamepadInput),
+    /// The loop is requested to exit with the given code
+    Exit(i32),
+    /// The game is asked to exit the loop (See
+    /// [on_exit_request](crate::Game::on_exit_request))
+    ExitRequest(ExitRequest),
+}
+
+/// Input state handler replaying a fixed script of input events.
+///
+/// Events are scheduled for a specific update cycle. The first call to the
+/// update function is cycle `0`. Frames without an update (eg. while the
+/// clock is paused) receive the events of the upcoming cycle already.
+///
+/// # Example
+/// ```
+/// use pixel_loop::input::{HeadlessInputState, KeyboardKey, MouseButton};
+///
+/// let input = HeadlessInputState::new()
+///     .with_key_tap(0, KeyboardKey::Space)
+///     .with_key_press(10, KeyboardKey::Right)
+///     .with_key_release(20, KeyboardKey::Right)
+///     .with_mouse_move(25, 4, 2)
+///     .with_mouse_click(25, MouseButton::Left)
+///     .with_exit(30, 0);
+/// ```
+pub struct HeadlessInputState {
+    script: BTreeMap<usize, Vec<ScriptedInput>>,
+    next_update: usize,
+    polled: bool,
+    keys_down: HashSet<KeyboardKey>,
+    keys_pressed_this_update: HashSet<KeyboardKey>,
+    keys_released_this_update: HashSet<KeyboardKey>,
+    keys_repeated_this_update: HashSet<KeyboardKey>,
+    text_this_update: String,
+    mouse_position: Option<(u32, u32)>,
+    mouse_buttons_down: HashSet<MouseButton>,
+    mouse_buttons_pressed_this_update: HashSet<MouseButton>,
+    mouse_buttons_released_this_update: HashSet<MouseButton>,
+    mouse_wheel_delta: (f32, f32),
+    gamepads: VirtualGamepadState,
+    exit_request: Option<ExitRequest>,
+}
+
+impl Default for HeadlessInputState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeadlessInputState {
+    /// Creates a new HeadlessInputState with an empty script.
+    pub fn new() -> Self {
+        Self {
+            script: BTreeMap::new(),
+            next_update: 0,
+            polled: false,
+            keys_down: HashSet::new(),
+            keys_pressed_this_update: HashSet::new(),
+            keys_released_this_update: HashSet::new(),
+            keys_repeated_this_update: HashSet::new(),
+            text_this_update: String::new(),
+            mouse_position: None,
+            mouse_buttons_down: HashSet::new(),
+            mouse_buttons_pressed_this_update: HashSet::new(),
+            mouse_buttons_released_this_update: HashSet::new(),
+            mouse_wheel_delta: (0.0, 0.0),
+            gamepads: VirtualGamepadState::new(),
+            exit_request: None,
+        }
+    }
+
+    /// Schedules an arbitrary [ScriptedInput] for the given update cycle.
+    pub fn with_event(mut self, update: usize, event: ScriptedInput) -> Self {
+        self.script.entry(update).or_default().push(event);
+        self
+    }
+
+    /// Schedules a key to be pressed down in the given update cycle.
+    pub fn with_key_press(self, update: usize, key: KeyboardKey) -> Self {
+        self.with_event(update, ScriptedInput::KeyPress(key))
+    }
+
+    /// Schedules a key to be released in the given update cycle.
+    pub fn with_key_release(self, update: usize, key: KeyboardKey) -> Self {
+        self.with_event(update, ScriptedInput::KeyRelease(key))
+    }
+
+    /// Schedules a key repeat of a key held down in the given update cycle.
+    ///
+    /// Keys, which are not held down in this cycle, are not repeated.
+    pub fn with_key_repeat(self, update: usize, key: KeyboardKey) -> Self {
+        self.with_event(update, ScriptedInput::KeyRepeat(key))
+    }
+
+    /// Schedules a key to be pressed in the given update cycle and released in
+    /// the following one.
+    pub fn with_key_tap(self, update: usize, key: KeyboardKey) -> Self {
+        self.with_key_press(update, key)
+            .with_key_release(update + 1, key)
+    }
+
+    /// Schedules the given text to be typed in the given update cycle.
+    ///
+    /// Only the text is scripted. No keys are pressed or released.
+    pub fn with_text(self, update: usize, text: &str) -> Self {
+        text.chars().fold(self, |input, character| {
+            input.with_event(update, ScriptedInput::Character(character))
+        })
+    }
+
+    /// Schedules the mouse cursor to be moved to the given position on the
+    /// canvas in the given update cycle.
+    pub fn with_mouse_move(self, update: usize, x: u32, y: u32) -> Self {
+        self.with_event(update, ScriptedInput::MouseMove(Some((x, y))))
+    }
+
+    /// Schedules a mouse button to be pressed down in the given update cycle.
+    pub fn with_mouse_button_press(self, update: usize, button: MouseButton) -> Self {
+        self.with_event(update, ScriptedInput::MouseButtonPress(button))
+    }
+
+    /// Schedules a mouse button to be released in the given update cycle.
+    pub fn with_mouse_button_release(self, update: usize, button: MouseButton) -> Self {
+        self.with_event(update, ScriptedInput::MouseButtonRelease(button))
+    }
+
+    /// Schedules a mouse button to be pressed in the given update cycle and
+    /// released in the following one.
+    pub fn with_mouse_click(self, update: usize, button: MouseButton) -> Self {
+        self.with_mouse_button_press(update, button)
+            .with_mouse_button_release(update + 1, button)
+    }
+
+    /// Schedules the mouse wheel to be scrolled by the given number of lines
+    /// in the given update cycle.
+    pub fn with_mouse_wheel(self, update: usize, x: f32, y: f32) -> Self {
+        self.with_event(update, ScriptedInput::MouseWheel(x, y))
+    }
+
+    /// Schedules a change of the state of a gamepad in the given update cycle.
+    ///
+    /// Gamepads are connected implicitly by the first change scheduled for
+    /// them.
+    pub fn with_gamepad_event(
+        self,
+        update: usize,
+        gamepad: GamepadId,
+        input: GamepadInput,
+    ) -> Self {
+        self.with_event(update, ScriptedInput::Gamepad(gamepad, input))
+    }
+
+    /// Schedules a gamepad button to be pressed down in the given update cycle.
+    pub fn with_gamepad_button_press(
+        self,
+        update: usize,
+        gamepad: GamepadId,
+        button: GamepadButton,
+    ) -> Self {
+        self.with_gamepad_event(update, gamepad, GamepadInput::ButtonPress(button))
+    }
+
+    /// Schedules a gamepad button to be released in the given update cycle.
+    pub fn with_gamepad_button_release(
+        self,
+        update: usize,
+        gamepad: GamepadId,
+        button: GamepadButton,
+    ) -> Self {
+        self.with_gamepad_event(update, gamepad, GamepadInput::ButtonRelease(button))
+    }
+
+    /// Schedules a gamepad button to be pressed in the given update cycle and
+    /// released in the following one.
+    pub fn with_gamepad_button_tap(
+        self,
+        update: usize,
+        gamepad: GamepadId,
+        button: GamepadButton,
+    ) -> Self {
+        self.with_gamepad_button_press(update, gamepad, button)
+            .with_gamepad_button_release(update + 1, gamepad, button)
+    }
+
+    /// Schedules an analog stick of a gamepad to be moved to the given
+    /// position in the given update cycle.
+    pub fn with_gamepad_stick(
+        self,
+        update: usize,
+        gamepad: GamepadId,
+        stick: GamepadStick,
+        x: f32,
+        y: f32,
+    ) -> Self {
+        self.with_gamepad_event(update, gamepad, GamepadInput::Stick(stick, x, y))
+    }
+
+    /// Schedules an analog trigger of a gamepad to be pulled to the given
+    /// value in the given update cycle.
+    pub fn with_gamepad_trigger(
+        self,
+        update: usize,
+        gamepad: GamepadId,
+        trigger: GamepadTrigger,
+        value: f32,
+    ) -> Self {
+        self.with_gamepad_event(update, gamepad, GamepadInput::Trigger(trigger, value))
+    }
+
+    /// Sets the deadzone applied to analog sticks of the scripted gamepads
+    /// (See [apply_deadzone](super::apply_deadzone)).
+    pub fn with_gamepad_deadzone(mut self, deadzone: f32) -> Self {
+        self.gamepads = self.gamepads.with_deadzone(deadzone);
+        self
+    }
+
+    /// Schedules an exit of the loop with the given code in the given update
+    /// cycle.
+    ///
+    /// The update function is not called anymore for this cycle.
+    pub fn with_exit(self, update: usize, code: i32) -> Self {
+        self.with_event(update, ScriptedInput::Exit(code))
+    }
+
+    /// Schedules a request to exit the loop in the given update cycle, eg. to
+    /// simulate pressing Ctrl-C.
+    ///
+    /// In contrast to [with_exit](HeadlessInputState::with_exit) the game
+    /// decides, whether the loop is actually exited (See
+    /// [on_exit_request](crate::Game::on_exit_request)).
+    pub fn with_exit_request(self, update: usize, request: ExitRequest) -> Self {
+        self.with_event(update, ScriptedInput::ExitRequest(request))
+    }
+
+    /// The number of update cycles processed so far.
+    pub fn updates(&self) -> usize {
+        self.next_update
+    }
+}
+
+impl InputState for HeadlessInputState {
+    fn begin(&mut self) -> Result<()> {
+        // Nothing to do here
+        Ok(())
+    }
+
+    fn next_loop(&mut self) -> Result<NextLoopState> {
+        let next = self.poll()?;
+        self.polled = false;
+        self.next_update += 1;
+        Ok(next)
+    }
+
+    fn poll(&mut self) -> Result<NextLoopState> {
+        if !self.polled {
+            self.keys_pressed_this_update.clear();
+            self.keys_released_this_update.clear();
+            self.keys_repeated_this_update.clear();
+            self.text_this_update.clear();
+            self.mouse_buttons_pressed_this_update.clear();
+            self.mouse_buttons_released_this_update.clear();
+            self.mouse_wheel_delta = (0.0, 0.0);
+            self.gamepads.next_update();
+            self.polled = true;
+        }
+
+        let events = self.script.remove(&self.next_update).unwrap_or_default();
+        for event in events {
+            match event {
+                ScriptedInput::KeyPress(key) => {
+                    if self.keys_down.insert(key) {
+                        self.keys_pressed_this_update.insert(key);
+                    }
+                }
+                ScriptedInput::KeyRelease(key) => {
+                    if self.keys_down.remove(&key) {
+                        self.keys_released_this_update.insert(key);
+                    }
+                }
+                ScriptedInput::KeyRepeat(key) => {
+                    if self.keys_down.contains(&key) {
+                        self.keys_repeated_this_update.insert(key);
+                    }
+                }
+                ScriptedInput::Character(character) => {
+                    self.text_this_update.push(character);
+                }
+                ScriptedInput::MouseMove(position) => {
+                    self.mouse_position = position;
+                }
+                ScriptedInput::MouseButtonPress(button) => {
+                    if self.mouse_buttons_down.insert(button) {
+                        self.mouse_buttons_pressed_this_update.insert(button);
+                    }
+                }
+                ScriptedInput::MouseButtonRelease(button) => {
+                    if self.mouse_buttons_down.remove(&button) {
+                        self.mouse_buttons_released_this_update.insert(button);
+                    }
+                }
+                ScriptedInput::MouseWheel(x, y) => {
+                    self.mouse_wheel_delta.0 += x;
+                    self.mouse_wheel_delta.1 += y;
+                }
+                ScriptedInput::Gamepad(gamepad, input) => {
+                    self.gamepads.apply(gamepad, input);
+                }
+                ScriptedInput::Exit(code) => return Ok(NextLoopState::Exit(code)),
+                ScriptedInput::ExitRequest(request) => self.exit_request = Some(request),
+            }
+        }
+
+        Ok(NextLoopState::Continue)
+    }
+
+    fn take_exit_request(&mut self) -> Option<ExitRequest> {
+        self.exit_request.take()
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        // Nothing to do here
+        Ok(())
+    }
+}
+
+impl KeyboardState for HeadlessInputState {
+    fn is_key_pressed(&self, key: KeyboardKey) -> bool {
+        self.keys_pressed_this_update.contains(&key)
+    }
+
+    fn is_key_down(&self, key: KeyboardKey) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    fn is_key_released(&self, key: KeyboardKey) -> bool {
+        self.keys_released_this_update.contains(&key)
+    }
+
+    fn is_key_up(&self, key: KeyboardKey) -> bool {
+        !self.keys_down.contains(&key)
+    }
+
+    fn is_key_repeated(&self, key: KeyboardKey) -> bool {
+        self.keys_repeated_this_update.contains(&key)
+    }
+}
+
+impl MouseState for HeadlessInputState {
+    fn mouse_position(&self) -> Option<(u32, u32)> {
+        self.mouse_position
+    }
+
+    fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_pressed_this_update.contains(&button)
+    }
+
+    fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_down.contains(&button)
+    }
+
+    fn is_mouse_button_released(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_released_this_update.contains(&button)
+    }
+
+    fn mouse_wheel_delta(&self) -> (f32, f32) {
+        self.mouse_wheel_delta
+    }
+}
+
+impl TextInputState for HeadlessInputState {
+    fn text_input(&self) -> &str {
+        &self.text_this_update
+    }
+}
+
+impl GamepadState for HeadlessInputState {
+    fn gamepads(&self) -> Vec<GamepadId> {
+        self.gamepads.gamepads()
+    }
+
+    fn is_gamepad_connected(&self, gamepad: GamepadId) -> bool {
+        self.gamepads.is_gamepad_connected(gamepad)
+    }
+
+    fn is_gamepad_button_pressed(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads.is_gamepad_button_pressed(gamepad, button)
+    }
+
+    fn is_gamepad_button_down(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads.is_gamepad_button_down(gamepad, button)
+    }
+
+    fn is_gamepad_button_released(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads.is_gamepad_button_released(gamepad, button)
+    }
+
+    fn gamepad_stick(&self, gamepad: GamepadId, stick: GamepadStick) -> (f32, f32) {
+        self.gamepads.gamepad_stick(gamepad, stick)
+    }
+
+    fn gamepad_trigger(&self, gamepad: GamepadId, trigger: GamepadTrigger) -> f32 {
+        self.gamepads.gamepad_trigger(gamepad, trigger)
+    }
+}
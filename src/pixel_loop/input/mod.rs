@@ -1,22 +1,42 @@
-//! Input handling and keyboard state management.
+//! Input handling and keyboard and mouse state management.
 //!
-//! This module provides traits and types for handling keyboard input across different
-//! platforms. It defines a comprehensive set of keyboard keys and traits for tracking
-//! keyboard state and input processing.
+//! This module provides traits and types for handling keyboard, mouse and gamepad input
+//! across different platforms. It defines a comprehensive set of keyboard keys and traits
+//! for tracking keyboard, mouse and gamepad state and input processing.
+
+pub mod action;
+pub use action::{ActionMap, Binding};
 
 #[cfg(feature = "crossterm")]
 pub mod crossterm;
 #[cfg(feature = "crossterm")]
 pub use crossterm::CrosstermInputState;
 
+pub mod gamepad;
+pub use gamepad::VirtualGamepadState;
+
+#[cfg(feature = "gamepad")]
+pub mod gilrs;
+#[cfg(feature = "gamepad")]
+pub use gilrs::GilrsGamepadState;
+
+pub mod headless;
+pub use headless::HeadlessInputState;
+
 #[cfg(feature = "pixels")]
 pub mod pixels;
 #[cfg(feature = "pixels")]
 pub use pixels::PixelsInputState;
 
+pub mod recording;
+pub use recording::RecordingInputState;
+
+pub mod replay;
+pub use replay::ReplayInputState;
+
 use anyhow::Result;
 
-use crate::NextLoopState;
+use crate::{ExitRequest, NextLoopState};
 
 /// Represents all possible keyboard keys that can be handled.
 ///
@@ -246,6 +266,147 @@ pub enum KeyboardKey {
     KpEqual,
 }
 
+impl KeyboardKey {
+    /// All keys, which can be handled, in declaration order.
+    pub const ALL: [KeyboardKey; 105] = [
+        KeyboardKey::Apostrophe,
+        KeyboardKey::Comma,
+        KeyboardKey::Minus,
+        KeyboardKey::Period,
+        KeyboardKey::Slash,
+        KeyboardKey::Zero,
+        KeyboardKey::One,
+        KeyboardKey::Two,
+        KeyboardKey::Three,
+        KeyboardKey::Four,
+        KeyboardKey::Five,
+        KeyboardKey::Six,
+        KeyboardKey::Seven,
+        KeyboardKey::Eight,
+        KeyboardKey::Nine,
+        KeyboardKey::Semicolon,
+        KeyboardKey::Equal,
+        KeyboardKey::A,
+        KeyboardKey::B,
+        KeyboardKey::C,
+        KeyboardKey::D,
+        KeyboardKey::E,
+        KeyboardKey::F,
+        KeyboardKey::G,
+        KeyboardKey::H,
+        KeyboardKey::I,
+        KeyboardKey::J,
+        KeyboardKey::K,
+        KeyboardKey::L,
+        KeyboardKey::M,
+        KeyboardKey::N,
+        KeyboardKey::O,
+        KeyboardKey::P,
+        KeyboardKey::Q,
+        KeyboardKey::R,
+        KeyboardKey::S,
+        KeyboardKey::T,
+        KeyboardKey::U,
+        KeyboardKey::V,
+        KeyboardKey::W,
+        KeyboardKey::X,
+        KeyboardKey::Y,
+        KeyboardKey::Z,
+        KeyboardKey::LeftBracket,
+        KeyboardKey::Backslash,
+        KeyboardKey::RightBracket,
+        KeyboardKey::Grave,
+        KeyboardKey::Space,
+        KeyboardKey::Escape,
+        KeyboardKey::Enter,
+        KeyboardKey::Tab,
+        KeyboardKey::Backspace,
+        KeyboardKey::Insert,
+        KeyboardKey::Delete,
+        KeyboardKey::Right,
+        KeyboardKey::Left,
+        KeyboardKey::Down,
+        KeyboardKey::Up,
+        KeyboardKey::PageUp,
+        KeyboardKey::PageDown,
+        KeyboardKey::Home,
+        KeyboardKey::End,
+        KeyboardKey::CapsLock,
+        KeyboardKey::ScrollLock,
+        KeyboardKey::NumLock,
+        KeyboardKey::PrintScreen,
+        KeyboardKey::Pause,
+        KeyboardKey::F1,
+        KeyboardKey::F2,
+        KeyboardKey::F3,
+        KeyboardKey::F4,
+        KeyboardKey::F5,
+        KeyboardKey::F6,
+        KeyboardKey::F7,
+        KeyboardKey::F8,
+        KeyboardKey::F9,
+        KeyboardKey::F10,
+        KeyboardKey::F11,
+        KeyboardKey::F12,
+        KeyboardKey::LeftShift,
+        KeyboardKey::LeftControl,
+        KeyboardKey::LeftAlt,
+        KeyboardKey::LeftSuper,
+        KeyboardKey::RightShift,
+        KeyboardKey::RightControl,
+        KeyboardKey::RightAlt,
+        KeyboardKey::RightSuper,
+        KeyboardKey::KbMenu,
+        KeyboardKey::Kp0,
+        KeyboardKey::Kp1,
+        KeyboardKey::Kp2,
+        KeyboardKey::Kp3,
+        KeyboardKey::Kp4,
+        KeyboardKey::Kp5,
+        KeyboardKey::Kp6,
+        KeyboardKey::Kp7,
+        KeyboardKey::Kp8,
+        KeyboardKey::Kp9,
+        KeyboardKey::KpDecimal,
+        KeyboardKey::KpDivide,
+        KeyboardKey::KpMultiply,
+        KeyboardKey::KpSubtract,
+        KeyboardKey::KpAdd,
+        KeyboardKey::KpEnter,
+        KeyboardKey::KpEqual,
+    ];
+}
+
+impl std::fmt::Display for KeyboardKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
+impl std::str::FromStr for KeyboardKey {
+    type Err = anyhow::Error;
+
+    /// Parses a key from its name as used by its [Display](std::fmt::Display)
+    /// implementation (eg. `Space` or `LeftShift`).
+    ///
+    /// # Example
+    /// ```
+    /// use pixel_loop::input::KeyboardKey;
+    ///
+    /// let key: KeyboardKey = "LeftShift".parse()?;
+    /// assert_eq!(key, KeyboardKey::LeftShift);
+    /// assert_eq!(key.to_string(), "LeftShift");
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    fn from_str(name: &str) -> Result<Self> {
+        KeyboardKey::ALL
+            .iter()
+            .find(|key| key.to_string() == name)
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("unknown keyboard key {name:?}"))
+    }
+}
+
 /// Trait for tracking keyboard state.
 ///
 /// This trait provides methods for checking the current state of keyboard keys,
@@ -275,16 +436,399 @@ pub trait KeyboardState {
     /// # Arguments
     /// * `key` - The key to check
     fn is_key_up(&self, key: KeyboardKey) -> bool;
+
+    /// Checks if a key, which is being held down, was repeated this frame by
+    /// the key repeat of the system.
+    ///
+    /// This is useful for menus or text fields, where holding down a key
+    /// should repeat its action in the configured repeat rate of the user.
+    ///
+    /// # Arguments
+    /// * `key` - The key to check
+    fn is_key_repeated(&self, key: KeyboardKey) -> bool;
+}
+
+/// Represents all mouse buttons that can be handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    /// Primary (usually left) mouse button
+    Left,
+    /// Secondary (usually right) mouse button
+    Right,
+    /// Middle mouse button (usually the wheel)
+    Middle,
+}
+
+impl MouseButton {
+    /// All mouse buttons, which can be handled, in declaration order.
+    pub const ALL: [MouseButton; 3] = [MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+}
+
+impl std::fmt::Display for MouseButton {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
+impl std::str::FromStr for MouseButton {
+    type Err = anyhow::Error;
+
+    /// Parses a mouse button from its name as used by its
+    /// [Display](std::fmt::Display) implementation (eg. `Left`).
+    fn from_str(name: &str) -> Result<Self> {
+        MouseButton::ALL
+            .iter()
+            .find(|button| button.to_string() == name)
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("unknown mouse button {name:?}"))
+    }
+}
+
+/// Trait for tracking mouse state.
+///
+/// Positions are reported in pixels of the canvas, not in physical pixels of
+/// the window or cells of the terminal.
+///
+/// # Example
+/// ```
+/// use pixel_loop::canvas::HeadlessCanvas;
+/// use pixel_loop::input::{HeadlessInputState, MouseButton, MouseState};
+/// use pixel_loop::NextLoopState;
+///
+/// let input = HeadlessInputState::new()
+///     .with_mouse_move(2, 4, 6)
+///     .with_mouse_click(3, MouseButton::Left);
+///
+/// let result = pixel_loop::run_headless(
+///     60,
+///     vec![],
+///     input,
+///     HeadlessCanvas::new(8, 8).with_frames(10),
+///     |_, clicks, input, _| {
+///         if input.is_mouse_button_pressed(MouseButton::Left) {
+///             clicks.extend(input.mouse_position());
+///         }
+///         Ok(NextLoopState::Continue)
+///     },
+///     |_, _, _, _, _| Ok(NextLoopState::Continue),
+/// )?;
+///
+/// assert_eq!(result.state, vec![(4, 6)]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub trait MouseState {
+    /// Get the position of the mouse cursor on the canvas (x, y).
+    ///
+    /// # Returns
+    /// * `Some((u32, u32))` - If the cursor is positioned above the canvas
+    /// * `None` - If the cursor is outside of the canvas, or its position is
+    ///   not known (yet)
+    fn mouse_position(&self) -> Option<(u32, u32)>;
+
+    /// Checks if a mouse button was pressed this frame.
+    ///
+    /// # Arguments
+    /// * `button` - The button to check
+    fn is_mouse_button_pressed(&self, button: MouseButton) -> bool;
+
+    /// Checks if a mouse button is currently being held down.
+    ///
+    /// # Arguments
+    /// * `button` - The button to check
+    fn is_mouse_button_down(&self, button: MouseButton) -> bool;
+
+    /// Checks if a mouse button was released this frame.
+    ///
+    /// # Arguments
+    /// * `button` - The button to check
+    fn is_mouse_button_released(&self, button: MouseButton) -> bool;
+
+    /// Checks if a mouse button is currently up (not being pressed).
+    ///
+    /// # Arguments
+    /// * `button` - The button to check
+    fn is_mouse_button_up(&self, button: MouseButton) -> bool {
+        !self.is_mouse_button_down(button)
+    }
+
+    /// Get the distance the mouse wheel has been scrolled this frame in lines
+    /// (x, y).
+    ///
+    /// Positive values represent scrolling right and up (away from the user).
+    fn mouse_wheel_delta(&self) -> (f32, f32);
+}
+
+/// Trait for receiving typed text.
+///
+/// In contrast to [KeyboardState], which reports physical keys, the typed text
+/// respects modifiers like shift, the keyboard layout of the user and
+/// characters composed by the system (eg. dead keys). It is therefore the
+/// foundation for things like name entry fields or chat boxes.
+///
+/// Control characters are not part of the typed text. Keys like backspace or
+/// enter are handled using the [KeyboardState] instead.
+///
+/// # Example
+/// ```
+/// use pixel_loop::canvas::HeadlessCanvas;
+/// use pixel_loop::input::{HeadlessInputState, KeyboardKey, KeyboardState, TextInputState};
+/// use pixel_loop::NextLoopState;
+///
+/// let input = HeadlessInputState::new()
+///     .with_text(0, "Pixel")
+///     .with_text(1, "s")
+///     .with_key_tap(2, KeyboardKey::Backspace);
+///
+/// let result = pixel_loop::run_headless(
+///     60,
+///     String::new(),
+///     input,
+///     HeadlessCanvas::new(8, 8).with_frames(10),
+///     |_, name, input, _| {
+///         name.push_str(input.text_input());
+///         if input.is_key_pressed(KeyboardKey::Backspace) {
+///             name.pop();
+///         }
+///         Ok(NextLoopState::Continue)
+///     },
+///     |_, _, _, _, _| Ok(NextLoopState::Continue),
+/// )?;
+///
+/// assert_eq!(result.state, "Pixel");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub trait TextInputState {
+    /// Get the text typed this frame, in the order it has been typed.
+    fn text_input(&self) -> &str;
+}
+
+/// Identifier of a connected gamepad.
+///
+/// Identifiers are assigned by the input implementation. They stay stable for
+/// the lifetime of a loop, even if a gamepad is disconnected and reconnected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GamepadId(pub usize);
+
+/// Represents the buttons of a gamepad.
+///
+/// Face buttons are named by their position, to be independent of the
+/// labeling of a specific gamepad model (eg. `South` is `A` on Xbox and `Cross`
+/// on PlayStation controllers).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    /// Lower face button
+    South,
+    /// Right face button
+    East,
+    /// Upper face button
+    North,
+    /// Left face button
+    West,
+    /// Left shoulder button
+    LeftBumper,
+    /// Right shoulder button
+    RightBumper,
+    /// Left menu button (eg. `Select` or `Back`)
+    Select,
+    /// Right menu button (eg. `Start` or `Options`)
+    Start,
+    /// Center button (eg. the Xbox or PlayStation button)
+    Mode,
+    /// Pressing the left analog stick
+    LeftStick,
+    /// Pressing the right analog stick
+    RightStick,
+    /// Up on the directional pad
+    DPadUp,
+    /// Down on the directional pad
+    DPadDown,
+    /// Left on the directional pad
+    DPadLeft,
+    /// Right on the directional pad
+    DPadRight,
+}
+
+impl GamepadButton {
+    /// All gamepad buttons, which can be handled, in declaration order.
+    pub const ALL: [GamepadButton; 15] = [
+        GamepadButton::South,
+        GamepadButton::East,
+        GamepadButton::North,
+        GamepadButton::West,
+        GamepadButton::LeftBumper,
+        GamepadButton::RightBumper,
+        GamepadButton::Select,
+        GamepadButton::Start,
+        GamepadButton::Mode,
+        GamepadButton::LeftStick,
+        GamepadButton::RightStick,
+        GamepadButton::DPadUp,
+        GamepadButton::DPadDown,
+        GamepadButton::DPadLeft,
+        GamepadButton::DPadRight,
+    ];
+}
+
+/// Represents the analog sticks of a gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadStick {
+    /// Left analog stick
+    Left,
+    /// Right analog stick
+    Right,
+}
+
+/// Represents the analog triggers of a gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadTrigger {
+    /// Left trigger
+    Left,
+    /// Right trigger
+    Right,
+}
+
+/// Deadzone of analog sticks used, if not configured otherwise.
+pub const DEFAULT_GAMEPAD_DEADZONE: f32 = 0.15;
+
+/// Applies a radial deadzone to the position of an analog stick (x, y).
+///
+/// Positions within the deadzone are reported as `(0.0, 0.0)`. Positions
+/// outside of it are rescaled, so that the reported distance from the center
+/// still covers the full range from `0.0` to `1.0`.
+///
+/// # Example
+/// ```
+/// use pixel_loop::input::apply_deadzone;
+///
+/// assert_eq!(apply_deadzone((0.25, 0.0), 0.5), (0.0, 0.0));
+/// assert_eq!(apply_deadzone((0.75, 0.0), 0.5), (0.5, 0.0));
+/// assert_eq!(apply_deadzone((0.0, -1.0), 0.5), (0.0, -1.0));
+/// ```
+pub fn apply_deadzone((x, y): (f32, f32), deadzone: f32) -> (f32, f32) {
+    let distance = (x * x + y * y).sqrt();
+    if distance <= deadzone || distance == 0.0 {
+        return (0.0, 0.0);
+    }
+    let scaled = ((distance - deadzone) / (1.0 - deadzone)).min(1.0);
+    (x / distance * scaled, y / distance * scaled)
+}
+
+/// Trait for tracking the state of gamepads.
+///
+/// In contrast to keyboard and mouse, multiple gamepads may be connected at
+/// the same time. Therefore every query is made for a specific gamepad.
+///
+/// This trait is not required by [InputState], as gamepads are not supported
+/// by every backend. Update functions, which want to handle gamepads, need to
+/// require it explicitly.
+///
+/// # Example
+/// ```
+/// use pixel_loop::canvas::HeadlessCanvas;
+/// use pixel_loop::input::{
+///     GamepadButton, GamepadId, GamepadState, GamepadStick, HeadlessInputState, InputState,
+/// };
+/// use pixel_loop::{EngineEnvironment, NextLoopState};
+/// use anyhow::Result;
+///
+/// fn update<I: InputState + GamepadState>(
+///     _env: &mut EngineEnvironment,
+///     (x, jumps): &mut (f32, u32),
+///     input: &I,
+///     _canvas: &mut HeadlessCanvas<I>,
+/// ) -> Result<NextLoopState> {
+///     for gamepad in input.gamepads() {
+///         *x += input.gamepad_stick(gamepad, GamepadStick::Left).0;
+///         if input.is_gamepad_button_pressed(gamepad, GamepadButton::South) {
+///             *jumps += 1;
+///         }
+///     }
+///     Ok(NextLoopState::Continue)
+/// }
+///
+/// let pad = GamepadId(0);
+/// let input = HeadlessInputState::new()
+///     .with_gamepad_stick(1, pad, GamepadStick::Left, 1.0, 0.0)
+///     .with_gamepad_button_tap(2, pad, GamepadButton::South)
+///     .with_gamepad_stick(3, pad, GamepadStick::Left, 0.0, 0.0);
+///
+/// let result = pixel_loop::run_headless(
+///     60,
+///     (0.0, 0),
+///     input,
+///     HeadlessCanvas::new(8, 8).with_frames(10),
+///     update,
+///     |_, _, _, _, _| Ok(NextLoopState::Continue),
+/// )?;
+///
+/// assert_eq!(result.state, (2.0, 1));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub trait GamepadState {
+    /// Get the identifiers of all currently connected gamepads in ascending
+    /// order.
+    fn gamepads(&self) -> Vec<GamepadId>;
+
+    /// Checks if the given gamepad is currently connected.
+    fn is_gamepad_connected(&self, gamepad: GamepadId) -> bool {
+        self.gamepads().contains(&gamepad)
+    }
+
+    /// Checks if a button of the given gamepad was pressed this frame.
+    ///
+    /// # Arguments
+    /// * `gamepad` - The gamepad to check
+    /// * `button` - The button to check
+    fn is_gamepad_button_pressed(&self, gamepad: GamepadId, button: GamepadButton) -> bool;
+
+    /// Checks if a button of the given gamepad is currently being held down.
+    ///
+    /// # Arguments
+    /// * `gamepad` - The gamepad to check
+    /// * `button` - The button to check
+    fn is_gamepad_button_down(&self, gamepad: GamepadId, button: GamepadButton) -> bool;
+
+    /// Checks if a button of the given gamepad was released this frame.
+    ///
+    /// # Arguments
+    /// * `gamepad` - The gamepad to check
+    /// * `button` - The button to check
+    fn is_gamepad_button_released(&self, gamepad: GamepadId, button: GamepadButton) -> bool;
+
+    /// Checks if a button of the given gamepad is currently up (not being
+    /// pressed).
+    ///
+    /// # Arguments
+    /// * `gamepad` - The gamepad to check
+    /// * `button` - The button to check
+    fn is_gamepad_button_up(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        !self.is_gamepad_button_down(gamepad, button)
+    }
+
+    /// Get the position of an analog stick of the given gamepad (x, y).
+    ///
+    /// Both axes range from `-1.0` to `1.0`, with positive values
+    /// representing right and up. The deadzone of the implementation has
+    /// already been applied (See [apply_deadzone]). Disconnected gamepads
+    /// report `(0.0, 0.0)`.
+    fn gamepad_stick(&self, gamepad: GamepadId, stick: GamepadStick) -> (f32, f32);
+
+    /// Get how far an analog trigger of the given gamepad is pulled, ranging
+    /// from `0.0` to `1.0`.
+    ///
+    /// Disconnected gamepads report `0.0`.
+    fn gamepad_trigger(&self, gamepad: GamepadId, trigger: GamepadTrigger) -> f32;
 }
 
 /// Trait for managing input state in a game loop.
 ///
-/// This trait extends `KeyboardState` and provides methods for managing input
-/// state throughout the lifecycle of a game loop.
+/// This trait extends `KeyboardState`, `MouseState` and `TextInputState` and
+/// provides methods for managing input state throughout the lifecycle of a
+/// game loop.
 ///
 /// Its methods provide a way for different platform implementations to hook
 /// into the game loop cycle to handle input event processing.
-pub trait InputState: KeyboardState {
+pub trait InputState: KeyboardState + MouseState + TextInputState {
     /// Initializes the input state before starting a loop.
     ///
     /// This method is called once before entering the main loop.
@@ -293,9 +837,31 @@ pub trait InputState: KeyboardState {
     /// Updates the input state for the next frame.
     ///
     /// This method is called at the beginning of each loop iteration, before the
-    /// update function is invoked.
+    /// update function is invoked. Input received by [poll](InputState::poll)
+    /// since the last update is part of the new state.
     fn next_loop(&mut self) -> Result<NextLoopState>;
 
+    /// Receives pending input within frames, in which no update is run (eg.
+    /// while the [Clock](crate::clock::Clock) is paused).
+    ///
+    /// In contrast to [next_loop](InputState::next_loop) the state of the
+    /// upcoming update cycle is not reset. Newly received input is added to
+    /// it instead, so that keys pressed in the meantime are still reported to
+    /// the next update. By default nothing is received.
+    fn poll(&mut self) -> Result<NextLoopState> {
+        Ok(NextLoopState::Continue)
+    }
+
+    /// Takes the pending request to exit the loop, which has been received
+    /// by the input implementation (eg. Ctrl-C).
+    ///
+    /// This method is called after each call to [next_loop](InputState::next_loop)
+    /// and [poll](InputState::poll). Requests are passed on to the [on_exit_request](crate::Game::on_exit_request)
+    /// hook of the game. By default no requests are received.
+    fn take_exit_request(&mut self) -> Option<ExitRequest> {
+        None
+    }
+
     /// Finalizes the input state after the loop ends.
     ///
     /// This method is called once after exiting the main loop.
@@ -4,7 +4,11 @@ use winit::event::{Event, VirtualKeyCode};
 
 use crate::NextLoopState;
 
-use super::{InputState, KeyboardKey, KeyboardState};
+#[cfg(feature = "gamepad")]
+use super::{
+    GamepadButton, GamepadId, GamepadState, GamepadStick, GamepadTrigger, GilrsGamepadState,
+};
+use super::{InputState, KeyboardKey, KeyboardState, MouseButton, MouseState, TextInputState};
 
 // Map winit keycodes to our KeyboardKey enum
 fn map_winit_key_to_pixel_loop(key: winit::event::VirtualKeyCode) -> Option<KeyboardKey> {
@@ -124,11 +128,33 @@ fn map_winit_key_to_pixel_loop(key: winit::event::VirtualKeyCode) -> Option<Keyb
         _ => None,
     }
 }
+// Map winit mouse buttons to our MouseButton enum
+fn map_winit_mouse_button_to_pixel_loop(button: winit::event::MouseButton) -> Option<MouseButton> {
+    match button {
+        winit::event::MouseButton::Left => Some(MouseButton::Left),
+        winit::event::MouseButton::Right => Some(MouseButton::Right),
+        winit::event::MouseButton::Middle => Some(MouseButton::Middle),
+        winit::event::MouseButton::Other(_) => None,
+    }
+}
+
+/// Number of pixels scrolled by touchpads, which are treated as one line.
+const PIXELS_PER_SCROLLED_LINE: f64 = 20.0;
+
 pub struct PixelsInputState {
     keys_down: HashSet<KeyboardKey>,
     keys_pressed_this_update: HashSet<KeyboardKey>,
     keys_released_this_update: HashSet<KeyboardKey>,
+    keys_repeated_this_update: HashSet<KeyboardKey>,
+    text_this_update: String,
+    mouse_position: Option<(u32, u32)>,
+    mouse_buttons_down: HashSet<MouseButton>,
+    mouse_buttons_pressed_this_update: HashSet<MouseButton>,
+    mouse_buttons_released_this_update: HashSet<MouseButton>,
+    mouse_wheel_delta: (f32, f32),
     clear_before_next_event: bool,
+    #[cfg(feature = "gamepad")]
+    gamepads: Option<GilrsGamepadState>,
 }
 
 impl PixelsInputState {
@@ -137,7 +163,16 @@ impl PixelsInputState {
             keys_down: HashSet::new(),
             keys_pressed_this_update: HashSet::new(),
             keys_released_this_update: HashSet::new(),
+            keys_repeated_this_update: HashSet::new(),
+            text_this_update: String::new(),
+            mouse_position: None,
+            mouse_buttons_down: HashSet::new(),
+            mouse_buttons_pressed_this_update: HashSet::new(),
+            mouse_buttons_released_this_update: HashSet::new(),
+            mouse_wheel_delta: (0.0, 0.0),
             clear_before_next_event: true,
+            #[cfg(feature = "gamepad")]
+            gamepads: None,
         }
     }
 
@@ -145,6 +180,11 @@ impl PixelsInputState {
         if self.clear_before_next_event {
             self.keys_pressed_this_update.clear();
             self.keys_released_this_update.clear();
+            self.keys_repeated_this_update.clear();
+            self.text_this_update.clear();
+            self.mouse_buttons_pressed_this_update.clear();
+            self.mouse_buttons_released_this_update.clear();
+            self.mouse_wheel_delta = (0.0, 0.0);
             self.clear_before_next_event = false;
         }
 
@@ -163,6 +203,10 @@ impl PixelsInputState {
                         if let Some(key) = map_winit_key_to_pixel_loop(*key) {
                             if !self.keys_down.contains(&key) {
                                 self.keys_pressed_this_update.insert(key);
+                            } else {
+                                // Pressing a key already held down is a
+                                // repetition by the system.
+                                self.keys_repeated_this_update.insert(key);
                             }
                             self.keys_down.insert(key);
                         }
@@ -175,21 +219,74 @@ impl PixelsInputState {
                         }
                     }
                 }
+                // Backspace, enter, etc. are handled as keys
+                winit::event::WindowEvent::ReceivedCharacter(character)
+                    if !character.is_control() =>
+                {
+                    self.text_this_update.push(*character);
+                }
+                winit::event::WindowEvent::MouseInput { state, button, .. } => {
+                    if let Some(button) = map_winit_mouse_button_to_pixel_loop(*button) {
+                        if *state == winit::event::ElementState::Pressed {
+                            if self.mouse_buttons_down.insert(button) {
+                                self.mouse_buttons_pressed_this_update.insert(button);
+                            }
+                        } else if self.mouse_buttons_down.remove(&button) {
+                            self.mouse_buttons_released_this_update.insert(button);
+                        }
+                    }
+                }
+                winit::event::WindowEvent::MouseWheel { delta, .. } => {
+                    let (x, y) = match delta {
+                        winit::event::MouseScrollDelta::LineDelta(x, y) => (*x, *y),
+                        winit::event::MouseScrollDelta::PixelDelta(position) => (
+                            (position.x / PIXELS_PER_SCROLLED_LINE) as f32,
+                            (position.y / PIXELS_PER_SCROLLED_LINE) as f32,
+                        ),
+                    };
+                    self.mouse_wheel_delta.0 += x;
+                    self.mouse_wheel_delta.1 += y;
+                }
+                winit::event::WindowEvent::CursorLeft { .. } => {
+                    self.mouse_position = None;
+                }
                 _ => {}
             },
             _ => {}
         }
     }
+
+    /// Sets the position of the mouse cursor on the canvas.
+    ///
+    /// Mapping window positions to canvas pixels requires knowledge about the
+    /// canvas, therefore this is done by the
+    /// [PixelsCanvas](crate::canvas::PixelsCanvas).
+    pub(crate) fn handle_mouse_position(&mut self, position: Option<(u32, u32)>) {
+        self.mouse_position = position;
+    }
+}
+
+impl AsMut<PixelsInputState> for PixelsInputState {
+    fn as_mut(&mut self) -> &mut PixelsInputState {
+        self
+    }
 }
 
 impl InputState for PixelsInputState {
     fn begin(&mut self) -> anyhow::Result<()> {
-        // Nothing to do here
+        #[cfg(feature = "gamepad")]
+        {
+            self.gamepads = Some(GilrsGamepadState::new()?);
+        }
         Ok(())
     }
 
     fn next_loop(&mut self) -> anyhow::Result<NextLoopState> {
         self.clear_before_next_event = true;
+        #[cfg(feature = "gamepad")]
+        if let Some(gamepads) = self.gamepads.as_mut() {
+            gamepads.next_update();
+        }
         Ok(NextLoopState::Continue)
     }
 
@@ -215,4 +312,77 @@ impl KeyboardState for PixelsInputState {
     fn is_key_up(&self, key: KeyboardKey) -> bool {
         !self.keys_down.contains(&key)
     }
+
+    fn is_key_repeated(&self, key: KeyboardKey) -> bool {
+        self.keys_repeated_this_update.contains(&key)
+    }
+}
+
+impl MouseState for PixelsInputState {
+    fn mouse_position(&self) -> Option<(u32, u32)> {
+        self.mouse_position
+    }
+
+    fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_pressed_this_update.contains(&button)
+    }
+
+    fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_down.contains(&button)
+    }
+
+    fn is_mouse_button_released(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_released_this_update.contains(&button)
+    }
+
+    fn mouse_wheel_delta(&self) -> (f32, f32) {
+        self.mouse_wheel_delta
+    }
+}
+
+impl TextInputState for PixelsInputState {
+    fn text_input(&self) -> &str {
+        &self.text_this_update
+    }
+}
+
+/// Gamepads are available once the loop has begun.
+#[cfg(feature = "gamepad")]
+impl GamepadState for PixelsInputState {
+    fn gamepads(&self) -> Vec<GamepadId> {
+        self.gamepads
+            .as_ref()
+            .map(GamepadState::gamepads)
+            .unwrap_or_default()
+    }
+
+    fn is_gamepad_button_pressed(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads
+            .as_ref()
+            .is_some_and(|gamepads| gamepads.is_gamepad_button_pressed(gamepad, button))
+    }
+
+    fn is_gamepad_button_down(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads
+            .as_ref()
+            .is_some_and(|gamepads| gamepads.is_gamepad_button_down(gamepad, button))
+    }
+
+    fn is_gamepad_button_released(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads
+            .as_ref()
+            .is_some_and(|gamepads| gamepads.is_gamepad_button_released(gamepad, button))
+    }
+
+    fn gamepad_stick(&self, gamepad: GamepadId, stick: GamepadStick) -> (f32, f32) {
+        self.gamepads.as_ref().map_or((0.0, 0.0), |gamepads| {
+            gamepads.gamepad_stick(gamepad, stick)
+        })
+    }
+
+    fn gamepad_trigger(&self, gamepad: GamepadId, trigger: GamepadTrigger) -> f32 {
+        self.gamepads
+            .as_ref()
+            .map_or(0.0, |gamepads| gamepads.gamepad_trigger(gamepad, trigger))
+    }
 }
@@ -0,0 +1,375 @@
+//! Recording of input states for a later deterministic replay.
+//!
+//! This module provides an input implementation wrapping any other
+//! [InputState]. It records the keyboard, text and mouse state of every update
+//! cycle, together with the seed of the random number generator, into a simple
+//! line based text format. Combined with the fixed timestep of the
+//! [PixelLoop](crate::PixelLoop) a recording can be replayed exactly using a
+//! [ReplayInputState](super::ReplayInputState), eg. within the
+//! [HeadlessCanvas](crate::canvas::HeadlessCanvas).
+//!
+//! # Format
+//! ```text
+//! pixel_loop-recording 1
+//! seed 1234
+//! 3 down Space
+//! 3 pressed Space
+//! 5 up Space
+//! 4 repeated Space
+//! 5 released Space
+//! 6 char 97
+//! 7 mouse 12 8
+//! 7 button_down Left
+//! 7 button_pressed Left
+//! 9 wheel 0 -1
+//! 20 mouse none
+//! 30 request Interrupt
+//! 42 exit 130
+//! end 43
+//! ```
+//!
+//! The header is followed by the seed. Every other line is prefixed with the
+//! update cycle it belongs to. Only changes of the set of keys and mouse
+//! buttons held down, as well as of the mouse position are recorded, while
+//! pressed, released and repeated keys and buttons, as well as the scrolled
+//! distance are listed for every cycle they occurred in. Typed text is recorded
+//! as one `char` line per character, containing its unicode code point.
+//! Requests to exit the loop (eg. Ctrl-C) are recorded as `request` lines,
+//! while the `exit` line states that the input implementation exited the loop
+//! on its own. The final `end` line states the number of recorded update
+//! cycles. It is missing, if the recording has not been finished properly, eg.
+//! due to a crash.
+
+use super::{InputState, KeyboardKey, KeyboardState, MouseButton, MouseState, TextInputState};
+use crate::{ExitRequest, NextLoopState};
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Header line identifying a recording and the version of its format.
+pub(crate) const RECORDING_HEADER: &str = "pixel_loop-recording 1";
+
+/// Input state handler recording the state of another input implementation.
+///
+/// All queries are answered by the wrapped input implementation. After each
+/// update cycle its keyboard, text and mouse state is written to the
+/// recording.
+///
+/// # Example
+/// ```
+/// use pixel_loop::canvas::HeadlessCanvas;
+/// use pixel_loop::input::{
+///     HeadlessInputState, InputState, KeyboardKey, RecordingInputState, ReplayInputState,
+/// };
+/// use pixel_loop::rand::Rng;
+/// use pixel_loop::{EngineEnvironment, FnGame, FrameInfo, NextLoopState, PixelLoop};
+/// use anyhow::Result;
+///
+/// fn update<I: InputState>(
+///     env: &mut EngineEnvironment,
+///     rolls: &mut Vec<u32>,
+///     input: &I,
+///     _canvas: &mut HeadlessCanvas<I>,
+/// ) -> Result<NextLoopState> {
+///     if input.is_key_pressed(KeyboardKey::Space) {
+///         rolls.push(env.rand.gen());
+///     }
+///     Ok(NextLoopState::Continue)
+/// }
+///
+/// fn render<I: InputState>(
+///     _env: &mut EngineEnvironment,
+///     _rolls: &mut Vec<u32>,
+///     _input: &I,
+///     _canvas: &mut HeadlessCanvas<I>,
+///     _frame: FrameInfo,
+/// ) -> Result<NextLoopState> {
+///     Ok(NextLoopState::Continue)
+/// }
+///
+/// let path = std::env::temp_dir().join("pixel_loop_recording_example.txt");
+///
+/// // Record a run, with input usually coming from a real device
+/// let env = EngineEnvironment::default();
+/// let input = RecordingInputState::create(
+///     HeadlessInputState::new().with_key_tap(3, KeyboardKey::Space),
+///     env.seed(),
+///     &path,
+/// )?;
+/// let canvas = HeadlessCanvas::new(8, 8)
+///     .with_input_type::<RecordingInputState<HeadlessInputState>>()
+///     .with_frames(10);
+/// let recorded = HeadlessCanvas::run_frames(
+///     PixelLoop::new(60, vec![], input, canvas, update, render).with_environment(env),
+/// )?
+/// .map_state(FnGame::into_state);
+///
+/// // Replay the recording
+/// let input = ReplayInputState::open(&path)?;
+/// let env = EngineEnvironment::default().with_seed(input.seed());
+/// let canvas = HeadlessCanvas::new(8, 8)
+///     .with_input_type::<ReplayInputState>()
+///     .with_frames(10);
+/// let replayed = HeadlessCanvas::run_frames(
+///     PixelLoop::new(60, vec![], input, canvas, update, render).with_environment(env),
+/// )?
+/// .map_state(FnGame::into_state);
+///
+/// assert_eq!(recorded.state.len(), 1);
+/// assert_eq!(recorded.state, replayed.state);
+/// # std::fs::remove_file(&path)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct RecordingInputState<I> {
+    inner: I,
+    writer: Box<dyn Write>,
+    seed: u64,
+    next_update: usize,
+    keys_down: HashSet<KeyboardKey>,
+    mouse_position: Option<(u32, u32)>,
+    mouse_buttons_down: HashSet<MouseButton>,
+    exit_request: Option<ExitRequest>,
+}
+
+impl<I: InputState> RecordingInputState<I> {
+    /// Creates a new recording of the given input implementation, which is
+    /// written to the given writer.
+    ///
+    /// # Arguments
+    /// * `inner` - The input implementation to record
+    /// * `seed` - The seed of the random number generator used by the loop
+    ///   (See [EngineEnvironment::seed](crate::EngineEnvironment::seed))
+    /// * `writer` - The target the recording is written to
+    pub fn new<W: Write + 'static>(inner: I, seed: u64, writer: W) -> Self {
+        Self {
+            inner,
+            writer: Box::new(writer),
+            seed,
+            next_update: 0,
+            keys_down: HashSet::new(),
+            mouse_position: None,
+            mouse_buttons_down: HashSet::new(),
+            exit_request: None,
+        }
+    }
+
+    /// Creates a new recording of the given input implementation, which is
+    /// written to a file at the given path.
+    ///
+    /// An already existing file is truncated.
+    pub fn create<P: AsRef<Path>>(inner: I, seed: u64, path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .with_context(|| format!("create input recording {}", path.display()))?;
+        Ok(Self::new(inner, seed, BufWriter::new(file)))
+    }
+
+    /// The seed stored in the recording.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The number of update cycles recorded so far.
+    pub fn updates(&self) -> usize {
+        self.next_update
+    }
+
+    /// Get a reference to the recorded input implementation.
+    pub fn inner(&self) -> &I {
+        &self.inner
+    }
+
+    /// Consumes the recording and returns the recorded input implementation.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+
+    fn record_update(&mut self, next: NextLoopState) -> Result<()> {
+        let update = self.next_update;
+        self.next_update += 1;
+
+        let mut lines = String::new();
+        for key in KeyboardKey::ALL {
+            let down = self.inner.is_key_down(key);
+            if down && self.keys_down.insert(key) {
+                writeln!(lines, "{update} down {key}")?;
+            } else if !down && self.keys_down.remove(&key) {
+                writeln!(lines, "{update} up {key}")?;
+            }
+
+            if self.inner.is_key_pressed(key) {
+                writeln!(lines, "{update} pressed {key}")?;
+            }
+            if self.inner.is_key_released(key) {
+                writeln!(lines, "{update} released {key}")?;
+            }
+            if self.inner.is_key_repeated(key) {
+                writeln!(lines, "{update} repeated {key}")?;
+            }
+        }
+
+        for character in self.inner.text_input().chars() {
+            writeln!(lines, "{update} char {}", character as u32)?;
+        }
+
+        let mouse_position = self.inner.mouse_position();
+        if mouse_position != self.mouse_position {
+            self.mouse_position = mouse_position;
+            match mouse_position {
+                Some((x, y)) => writeln!(lines, "{update} mouse {x} {y}")?,
+                None => writeln!(lines, "{update} mouse none")?,
+            }
+        }
+        for button in MouseButton::ALL {
+            let down = self.inner.is_mouse_button_down(button);
+            if down && self.mouse_buttons_down.insert(button) {
+                writeln!(lines, "{update} button_down {button}")?;
+            } else if !down && self.mouse_buttons_down.remove(&button) {
+                writeln!(lines, "{update} button_up {button}")?;
+            }
+
+            if self.inner.is_mouse_button_pressed(button) {
+                writeln!(lines, "{update} button_pressed {button}")?;
+            }
+            if self.inner.is_mouse_button_released(button) {
+                writeln!(lines, "{update} button_released {button}")?;
+            }
+        }
+        let (wheel_x, wheel_y) = self.inner.mouse_wheel_delta();
+        if wheel_x != 0.0 || wheel_y != 0.0 {
+            writeln!(lines, "{update} wheel {wheel_x} {wheel_y}")?;
+        }
+
+        self.write_exit_lines(update, next, &mut lines)?;
+        self.write_lines(&lines)
+    }
+
+    /// Records requests and exits received while polling without an update,
+    /// as part of the upcoming update cycle.
+    fn record_poll(&mut self, next: NextLoopState) -> Result<()> {
+        let mut lines = String::new();
+        self.write_exit_lines(self.next_update, next, &mut lines)?;
+        self.write_lines(&lines)
+    }
+
+    fn write_exit_lines(
+        &self,
+        update: usize,
+        next: NextLoopState,
+        lines: &mut String,
+    ) -> std::fmt::Result {
+        if let Some(request) = self.exit_request {
+            writeln!(lines, "{update} request {request}")?;
+        }
+        if let NextLoopState::Exit(code) = next {
+            writeln!(lines, "{update} exit {code}")?;
+        }
+        Ok(())
+    }
+
+    fn write_lines(&mut self, lines: &str) -> Result<()> {
+        if !lines.is_empty() {
+            // Flush every cycle, to keep the recording usable after a crash.
+            self.writer.write_all(lines.as_bytes())?;
+            self.writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, I: AsMut<T>> AsMut<T> for RecordingInputState<I> {
+    fn as_mut(&mut self) -> &mut T {
+        self.inner.as_mut()
+    }
+}
+
+impl<I: InputState> InputState for RecordingInputState<I> {
+    fn begin(&mut self) -> Result<()> {
+        self.inner.begin()?;
+        writeln!(self.writer, "{RECORDING_HEADER}")?;
+        writeln!(self.writer, "seed {}", self.seed)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn next_loop(&mut self) -> Result<NextLoopState> {
+        let next = self.inner.next_loop()?;
+        self.exit_request = self.inner.take_exit_request();
+        self.record_update(next).context("write input recording")?;
+        Ok(next)
+    }
+
+    fn poll(&mut self) -> Result<NextLoopState> {
+        let next = self.inner.poll()?;
+        self.exit_request = self.inner.take_exit_request();
+        self.record_poll(next).context("write input recording")?;
+        Ok(next)
+    }
+
+    fn take_exit_request(&mut self) -> Option<ExitRequest> {
+        self.exit_request.take()
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        writeln!(self.writer, "end {}", self.next_update)?;
+        self.writer.flush()?;
+        self.inner.finish()
+    }
+}
+
+impl<I: InputState> KeyboardState for RecordingInputState<I> {
+    fn is_key_pressed(&self, key: KeyboardKey) -> bool {
+        self.inner.is_key_pressed(key)
+    }
+
+    fn is_key_down(&self, key: KeyboardKey) -> bool {
+        self.inner.is_key_down(key)
+    }
+
+    fn is_key_released(&self, key: KeyboardKey) -> bool {
+        self.inner.is_key_released(key)
+    }
+
+    fn is_key_up(&self, key: KeyboardKey) -> bool {
+        self.inner.is_key_up(key)
+    }
+
+    fn is_key_repeated(&self, key: KeyboardKey) -> bool {
+        self.inner.is_key_repeated(key)
+    }
+}
+
+impl<I: InputState> MouseState for RecordingInputState<I> {
+    fn mouse_position(&self) -> Option<(u32, u32)> {
+        self.inner.mouse_position()
+    }
+
+    fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.inner.is_mouse_button_pressed(button)
+    }
+
+    fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+        self.inner.is_mouse_button_down(button)
+    }
+
+    fn is_mouse_button_released(&self, button: MouseButton) -> bool {
+        self.inner.is_mouse_button_released(button)
+    }
+
+    fn is_mouse_button_up(&self, button: MouseButton) -> bool {
+        self.inner.is_mouse_button_up(button)
+    }
+
+    fn mouse_wheel_delta(&self) -> (f32, f32) {
+        self.inner.mouse_wheel_delta()
+    }
+}
+
+impl<I: InputState> TextInputState for RecordingInputState<I> {
+    fn text_input(&self) -> &str {
+        self.inner.text_input()
+    }
+}
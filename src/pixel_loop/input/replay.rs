@@ -0,0 +1,336 @@
+//! Deterministic replay of recorded input states.
+//!
+//! This module provides an input implementation, which reads a recording
+//! created by a [RecordingInputState](super::RecordingInputState) and replays
+//! the recorded keyboard and mouse state for every update cycle. See the
+//! [recording](super::recording) module for a description of the format.
+
+use super::recording::RECORDING_HEADER;
+use super::{InputState, KeyboardKey, KeyboardState, MouseButton, MouseState, TextInputState};
+use crate::{ExitRequest, NextLoopState};
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// A single recorded change of the input state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RecordedInput {
+    Down(KeyboardKey),
+    Up(KeyboardKey),
+    Pressed(KeyboardKey),
+    Released(KeyboardKey),
+    Repeated(KeyboardKey),
+    Character(char),
+    MouseMove(Option<(u32, u32)>),
+    ButtonDown(MouseButton),
+    ButtonUp(MouseButton),
+    ButtonPressed(MouseButton),
+    ButtonReleased(MouseButton),
+    Wheel(f32, f32),
+    Request(ExitRequest),
+    Exit(i32),
+}
+
+/// Input state handler replaying a recording.
+///
+/// The first call to the update function receives the state recorded for
+/// update cycle `0`. Once the recording is exhausted, the last state is kept.
+///
+/// To reproduce a recorded run exactly, the random number generator of the
+/// loop needs to be seeded with the recorded [seed](ReplayInputState::seed).
+///
+/// # Example
+/// ```
+/// use pixel_loop::input::{KeyboardKey, ReplayInputState};
+/// use pixel_loop::EngineEnvironment;
+///
+/// let recording = "pixel_loop-recording 1\nseed 42\n0 down Space\n0 pressed Space\nend 1\n";
+/// let input = ReplayInputState::from_reader(recording.as_bytes())?;
+/// let env = EngineEnvironment::default().with_seed(input.seed());
+///
+/// assert_eq!(input.seed(), 42);
+/// assert_eq!(input.recorded_updates(), Some(1));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct ReplayInputState {
+    seed: u64,
+    recorded_updates: Option<usize>,
+    records: BTreeMap<usize, Vec<RecordedInput>>,
+    next_update: usize,
+    polled: bool,
+    keys_down: HashSet<KeyboardKey>,
+    keys_pressed_this_update: HashSet<KeyboardKey>,
+    keys_released_this_update: HashSet<KeyboardKey>,
+    keys_repeated_this_update: HashSet<KeyboardKey>,
+    text_this_update: String,
+    mouse_position: Option<(u32, u32)>,
+    mouse_buttons_down: HashSet<MouseButton>,
+    mouse_buttons_pressed_this_update: HashSet<MouseButton>,
+    mouse_buttons_released_this_update: HashSet<MouseButton>,
+    mouse_wheel_delta: (f32, f32),
+    exit_request: Option<ExitRequest>,
+}
+
+impl ReplayInputState {
+    /// Reads a recording from the file at the given path.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file =
+            File::open(path).with_context(|| format!("open input recording {}", path.display()))?;
+        Self::from_reader(BufReader::new(file))
+            .with_context(|| format!("read input recording {}", path.display()))
+    }
+
+    /// Reads a recording from the given reader.
+    ///
+    /// # Errors
+    /// Returns an error if the recording is malformed or of an unsupported
+    /// version.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self> {
+        let mut lines = reader.lines().enumerate();
+
+        let header = lines.next().map(|(_, line)| line).transpose()?;
+        if header.as_deref().map(str::trim) != Some(RECORDING_HEADER) {
+            bail!("missing header {RECORDING_HEADER:?}");
+        }
+
+        let seed = match lines.next() {
+            Some((_, line)) => {
+                let line = line?;
+                line.strip_prefix("seed ")
+                    .and_then(|seed| seed.trim().parse::<u64>().ok())
+                    .ok_or_else(|| anyhow!("line 2: expected seed, found {line:?}"))?
+            }
+            None => bail!("missing seed"),
+        };
+
+        let mut records: BTreeMap<usize, Vec<RecordedInput>> = BTreeMap::new();
+        let mut recorded_updates = None;
+        for (index, line) in lines {
+            let line = line?;
+            let mut parse = || -> Result<()> {
+                let fields = line.split_whitespace().collect::<Vec<_>>();
+                match fields.as_slice() {
+                    [] => {}
+                    ["end", updates] => recorded_updates = Some(updates.parse()?),
+                    [update, "mouse", "none"] => records
+                        .entry(update.parse()?)
+                        .or_default()
+                        .push(RecordedInput::MouseMove(None)),
+                    [update, "mouse", x, y] => records
+                        .entry(update.parse()?)
+                        .or_default()
+                        .push(RecordedInput::MouseMove(Some((x.parse()?, y.parse()?)))),
+                    [update, "wheel", x, y] => records
+                        .entry(update.parse()?)
+                        .or_default()
+                        .push(RecordedInput::Wheel(x.parse()?, y.parse()?)),
+                    [update, "char", code] => {
+                        let character = char::from_u32(code.parse()?)
+                            .ok_or_else(|| anyhow!("invalid character {code}"))?;
+                        records
+                            .entry(update.parse()?)
+                            .or_default()
+                            .push(RecordedInput::Character(character))
+                    }
+                    [update, "exit", code] => records
+                        .entry(update.parse()?)
+                        .or_default()
+                        .push(RecordedInput::Exit(code.parse()?)),
+                    [update, kind, name] => {
+                        let record = match *kind {
+                            "down" => RecordedInput::Down(name.parse()?),
+                            "up" => RecordedInput::Up(name.parse()?),
+                            "pressed" => RecordedInput::Pressed(name.parse()?),
+                            "released" => RecordedInput::Released(name.parse()?),
+                            "repeated" => RecordedInput::Repeated(name.parse()?),
+                            "button_down" => RecordedInput::ButtonDown(name.parse()?),
+                            "button_up" => RecordedInput::ButtonUp(name.parse()?),
+                            "button_pressed" => RecordedInput::ButtonPressed(name.parse()?),
+                            "button_released" => RecordedInput::ButtonReleased(name.parse()?),
+                            "request" => RecordedInput::Request(name.parse()?),
+                            _ => bail!("unknown record {kind:?}"),
+                        };
+                        records.entry(update.parse()?).or_default().push(record);
+                    }
+                    _ => bail!("unexpected number of fields"),
+                }
+                Ok(())
+            };
+            parse().with_context(|| format!("line {}: {line:?}", index + 1))?;
+        }
+
+        Ok(Self {
+            seed,
+            recorded_updates,
+            records,
+            next_update: 0,
+            polled: false,
+            keys_down: HashSet::new(),
+            keys_pressed_this_update: HashSet::new(),
+            keys_released_this_update: HashSet::new(),
+            keys_repeated_this_update: HashSet::new(),
+            text_this_update: String::new(),
+            mouse_position: None,
+            mouse_buttons_down: HashSet::new(),
+            mouse_buttons_pressed_this_update: HashSet::new(),
+            mouse_buttons_released_this_update: HashSet::new(),
+            mouse_wheel_delta: (0.0, 0.0),
+            exit_request: None,
+        })
+    }
+
+    /// The seed of the random number generator stored in the recording.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The number of update cycles stored in the recording.
+    ///
+    /// This is `None`, if the recording has not been finished properly, eg.
+    /// due to a crash.
+    pub fn recorded_updates(&self) -> Option<usize> {
+        self.recorded_updates
+    }
+
+    /// The number of update cycles replayed so far.
+    pub fn updates(&self) -> usize {
+        self.next_update
+    }
+}
+
+impl InputState for ReplayInputState {
+    fn begin(&mut self) -> Result<()> {
+        // Nothing to do here
+        Ok(())
+    }
+
+    fn next_loop(&mut self) -> Result<NextLoopState> {
+        let next = self.poll()?;
+        self.polled = false;
+        self.next_update += 1;
+        Ok(next)
+    }
+
+    fn poll(&mut self) -> Result<NextLoopState> {
+        if !self.polled {
+            self.keys_pressed_this_update.clear();
+            self.keys_released_this_update.clear();
+            self.keys_repeated_this_update.clear();
+            self.text_this_update.clear();
+            self.mouse_buttons_pressed_this_update.clear();
+            self.mouse_buttons_released_this_update.clear();
+            self.mouse_wheel_delta = (0.0, 0.0);
+            self.polled = true;
+        }
+
+        let records = self.records.remove(&self.next_update).unwrap_or_default();
+
+        let mut next = NextLoopState::Continue;
+        for record in records {
+            match record {
+                RecordedInput::Down(key) => {
+                    self.keys_down.insert(key);
+                }
+                RecordedInput::Up(key) => {
+                    self.keys_down.remove(&key);
+                }
+                RecordedInput::Pressed(key) => {
+                    self.keys_pressed_this_update.insert(key);
+                }
+                RecordedInput::Released(key) => {
+                    self.keys_released_this_update.insert(key);
+                }
+                RecordedInput::Repeated(key) => {
+                    self.keys_repeated_this_update.insert(key);
+                }
+                RecordedInput::Character(character) => {
+                    self.text_this_update.push(character);
+                }
+                RecordedInput::MouseMove(position) => {
+                    self.mouse_position = position;
+                }
+                RecordedInput::ButtonDown(button) => {
+                    self.mouse_buttons_down.insert(button);
+                }
+                RecordedInput::ButtonUp(button) => {
+                    self.mouse_buttons_down.remove(&button);
+                }
+                RecordedInput::ButtonPressed(button) => {
+                    self.mouse_buttons_pressed_this_update.insert(button);
+                }
+                RecordedInput::ButtonReleased(button) => {
+                    self.mouse_buttons_released_this_update.insert(button);
+                }
+                RecordedInput::Wheel(x, y) => {
+                    self.mouse_wheel_delta = (x, y);
+                }
+                RecordedInput::Request(request) => self.exit_request = Some(request),
+                RecordedInput::Exit(code) => next = NextLoopState::Exit(code),
+            }
+        }
+
+        Ok(next)
+    }
+
+    fn take_exit_request(&mut self) -> Option<ExitRequest> {
+        self.exit_request.take()
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        // Nothing to do here
+        Ok(())
+    }
+}
+
+impl KeyboardState for ReplayInputState {
+    fn is_key_pressed(&self, key: KeyboardKey) -> bool {
+        self.keys_pressed_this_update.contains(&key)
+    }
+
+    fn is_key_down(&self, key: KeyboardKey) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    fn is_key_released(&self, key: KeyboardKey) -> bool {
+        self.keys_released_this_update.contains(&key)
+    }
+
+    fn is_key_up(&self, key: KeyboardKey) -> bool {
+        !self.keys_down.contains(&key)
+    }
+
+    fn is_key_repeated(&self, key: KeyboardKey) -> bool {
+        self.keys_repeated_this_update.contains(&key)
+    }
+}
+
+impl MouseState for ReplayInputState {
+    fn mouse_position(&self) -> Option<(u32, u32)> {
+        self.mouse_position
+    }
+
+    fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_pressed_this_update.contains(&button)
+    }
+
+    fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_down.contains(&button)
+    }
+
+    fn is_mouse_button_released(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_released_this_update.contains(&button)
+    }
+
+    fn mouse_wheel_delta(&self) -> (f32, f32) {
+        self.mouse_wheel_delta
+    }
+}
+
+impl TextInputState for ReplayInputState {
+    fn text_input(&self) -> &str {
+        &self.text_this_update
+    }
+}
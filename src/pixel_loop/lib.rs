@@ -6,7 +6,7 @@
 //!
 //! # Example
 //! ```
-//! use pixel_loop::{run, EngineEnvironment};
+//! use pixel_loop::{run, EngineEnvironment, FrameInfo};
 //! use pixel_loop::canvas::{Canvas, CrosstermCanvas, RenderableCanvas};
 //! use pixel_loop::color::Color;
 //! use pixel_loop::input::{CrosstermInputState, KeyboardKey, KeyboardState};
@@ -56,7 +56,7 @@
 //!          state: &mut State,
 //!          input: &CrosstermInputState,
 //!          canvas: &mut CrosstermCanvas,
-//!          dt: std::time::Duration) -> Result<()> {
+//!          frame: FrameInfo) -> Result<()> {
 //!     canvas.clear_screen(&Color::from_rgb(0, 0, 0));
 //!     canvas.filled_rect(
 //!         state.box_entity.position.0,
@@ -75,8 +75,13 @@
 //! ```
 
 pub mod canvas;
+pub mod clock;
 pub mod color;
+pub mod game;
 pub mod input;
+pub mod recorder;
+pub mod sprite;
+pub mod text;
 
 // Re-exporting deps for convenience in code using pixel_loop
 #[cfg(feature = "crossterm")]
@@ -84,60 +89,92 @@ pub use crossterm;
 pub use rand;
 pub use rand_xoshiro;
 
-use anyhow::Result;
+pub use game::{FnGame, Game};
+
+use anyhow::{Context, Result};
 use canvas::RenderableCanvas;
+use clock::Clock;
 use input::InputState;
 use rand::SeedableRng;
 use rand_xoshiro::Xoshiro256PlusPlus;
-use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use recorder::Recorder;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-/// Function type for the update step of the game loop.
-///
-/// Called at a fixed timestep to update game state.
-///
-/// # Arguments
-/// * `env` - Global engine environment containing shared resources
-/// * `state` - Mutable reference to the game state
-/// * `input` - Reference to the current input state
-/// * `canvas` - Mutable reference to the rendering canvas
-///
-/// # Returns
-/// * `NextLoopState` - Determines if the game loop should continue or exit
-type UpdateFn<State, CanvasImpl> = fn(
-    &mut EngineEnvironment,
-    &mut State,
-    &<CanvasImpl as RenderableCanvas>::Input,
-    &mut CanvasImpl,
-) -> Result<NextLoopState>;
-
-/// Function type for the render step of the game loop.
-///
-/// Called as often as possible with the actual frame time delta.
-///
-/// # Arguments
-/// * `env` - Global engine environment containing shared resources
-/// * `state` - Mutable reference to the game state
-/// * `input` - Reference to the current input state
-/// * `canvas` - Mutable reference to the rendering canvas
-/// * `dt` - Time elapsed since last render
+/// Timing information about the current frame supplied to the render function.
 ///
-/// # Returns
-/// * `NextLoopState` - Determines if the game loop should continue or exit
-type RenderFn<State, CanvasImpl> = fn(
-    &mut EngineEnvironment,
-    &mut State,
-    &<CanvasImpl as RenderableCanvas>::Input,
-    &mut CanvasImpl,
-    Duration,
-) -> Result<NextLoopState>;
+/// Following the classic fixed timestep pattern, the `alpha` value can be used
+/// to interpolate between the previous and the current simulation state:
+/// ```
+/// # let frame = pixel_loop::FrameInfo { dt: Default::default(), alpha: 0.5, updates: 1, resized: false };
+/// # let (previous_x, current_x) = (10.0, 20.0);
+/// let x = previous_x + (current_x - previous_x) * frame.alpha;
+/// # assert_eq!(x, 15.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameInfo {
+    /// Time elapsed since the last frame, after time scaling and clamping by
+    /// the [Clock]
+    pub dt: Duration,
+    /// Time left in the update accumulator divided by the update timestep
+    /// [0.0-1.0)
+    pub alpha: f64,
+    /// Number of updates run within this frame
+    pub updates: u32,
+    /// Whether the size of the canvas changed since the last frame
+    ///
+    /// This is always the case for the very first frame.
+    pub resized: bool,
+}
 
 /// Global engine state containing shared resources.
 ///
 /// Provides access to engine-wide functionality and resources that
 /// are available to both update and render functions.
+///
+/// # Example
+/// ```
+/// use pixel_loop::EngineEnvironment;
+/// use pixel_loop::rand::Rng;
+///
+/// // Use an explicit seed, unless one is provided via the environment
+/// let mut env = EngineEnvironment::default()
+///     .with_seed(42)
+///     .with_seed_from_env(pixel_loop::SEED_ENV_VAR)?;
+/// eprintln!("Random seed: {}", env.seed());
+///
+/// let saved = env.rng_state();
+/// let first: u32 = env.rand.gen();
+/// env.restore_rng_state(&saved);
+/// assert_eq!(first, env.rand.gen::<u32>());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
 pub struct EngineEnvironment {
     /// Random number generator for game logic
-    pub rand: Box<dyn rand::RngCore>,
+    pub rand: Xoshiro256PlusPlus,
+    /// Clock driving the fixed timestep updates
+    pub clock: Clock,
+    /// The seed the random number generator was initialized with
+    seed: u64,
+}
+
+/// Name of the environment variable conventionally used to supply a seed for
+/// the random number generator (See
+/// [with_seed_from_env](EngineEnvironment::with_seed_from_env)).
+pub const SEED_ENV_VAR: &str = "PIXEL_LOOP_SEED";
+
+/// Snapshot of the state of the random number generator of an
+/// [EngineEnvironment].
+#[derive(Clone)]
+pub struct RngState {
+    seed: u64,
+    rand: Xoshiro256PlusPlus,
+}
+
+impl RngState {
+    /// The seed the random number generator was initialized with
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
 }
 
 impl Default for EngineEnvironment {
@@ -147,9 +184,80 @@ impl Default for EngineEnvironment {
             .expect("If time since UNIX_EPOCH is 0 there is something wrong?")
             .as_micros();
         Self {
-            rand: Box::new(Xoshiro256PlusPlus::seed_from_u64(micros as u64)),
+            rand: Xoshiro256PlusPlus::seed_from_u64(micros as u64),
+            clock: Clock::default(),
+            seed: micros as u64,
+        }
+    }
+}
+
+impl EngineEnvironment {
+    /// Replaces the clock driving the fixed timestep updates.
+    ///
+    /// # Example
+    /// ```
+    /// use pixel_loop::clock::Clock;
+    /// use pixel_loop::EngineEnvironment;
+    ///
+    /// let env = EngineEnvironment::default().with_clock(Clock::default().with_time_scale(0.5));
+    /// ```
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Initializes the random number generator with the given seed.
+    ///
+    /// By default the random number generator is seeded from the wall clock.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.reseed(seed);
+        self
+    }
+
+    /// Initializes the random number generator with a seed read from the
+    /// given environment variable.
+    ///
+    /// If the variable is not set, the current seed is kept.
+    ///
+    /// # Errors
+    /// Returns an error if the variable is set, but does not contain a valid
+    /// unsigned 64 bit integer.
+    pub fn with_seed_from_env(self, name: &str) -> Result<Self> {
+        match std::env::var(name) {
+            Ok(value) => {
+                let seed = value.trim().parse::<u64>().with_context(|| {
+                    format!("parse random seed from environment variable {name}={value:?}")
+                })?;
+                Ok(self.with_seed(seed))
+            }
+            Err(_) => Ok(self),
+        }
+    }
+
+    /// The seed the random number generator was initialized with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Reinitializes the random number generator with the given seed.
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rand = Xoshiro256PlusPlus::seed_from_u64(seed);
+    }
+
+    /// Takes a snapshot of the current state of the random number generator.
+    pub fn rng_state(&self) -> RngState {
+        RngState {
+            seed: self.seed,
+            rand: self.rand.clone(),
         }
     }
+
+    /// Restores the random number generator to a previously taken snapshot.
+    pub fn restore_rng_state(&mut self, state: &RngState) {
+        self.seed = state.seed;
+        self.rand = state.rand.clone();
+    }
 }
 
 /// Return type for the next loop state.
@@ -160,46 +268,133 @@ pub enum NextLoopState {
     Exit(i32),
 }
 
+/// Request to exit the loop, which did not originate from the game itself.
+///
+/// Requests are passed to the [on_exit_request](Game::on_exit_request) hook
+/// of the game, which decides whether the loop is actually exited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExitRequest {
+    /// Ctrl-C has been pressed, or SIGINT has been received
+    Interrupt,
+    /// SIGTERM has been received
+    Terminate,
+    /// SIGHUP has been received, eg. because the terminal has been closed
+    Hangup,
+    /// The window has been requested to close
+    Close,
+}
+
+impl ExitRequest {
+    /// All exit requests in declaration order.
+    pub const ALL: [ExitRequest; 4] = [
+        ExitRequest::Interrupt,
+        ExitRequest::Terminate,
+        ExitRequest::Hangup,
+        ExitRequest::Close,
+    ];
+
+    /// The conventional exit code for this request.
+    ///
+    /// Requests caused by signals use `128` plus the number of the signal,
+    /// like shells do. Closing the window is a regular exit with code `0`.
+    pub fn code(&self) -> i32 {
+        match self {
+            ExitRequest::Interrupt => 130,
+            ExitRequest::Terminate => 143,
+            ExitRequest::Hangup => 129,
+            ExitRequest::Close => 0,
+        }
+    }
+}
+
+impl std::fmt::Display for ExitRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
+impl std::str::FromStr for ExitRequest {
+    type Err = anyhow::Error;
+
+    /// Parses an exit request from its name as used by its
+    /// [Display](std::fmt::Display) implementation (eg. `Interrupt`).
+    fn from_str(name: &str) -> Result<Self> {
+        ExitRequest::ALL
+            .iter()
+            .find(|request| request.to_string() == name)
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("unknown exit request {name:?}"))
+    }
+}
+
+/// Exit code used, if the loop has been aborted due to an error.
+pub const ERROR_EXIT_CODE: i32 = 1;
+
+/// Default maximal number of updates run within a single frame (See
+/// [with_max_updates_per_frame](PixelLoop::with_max_updates_per_frame)).
+pub const DEFAULT_MAX_UPDATES_PER_FRAME: u32 = 240;
+
+/// Determines how errors returned by the [Game] are handled by the
+/// [PixelLoop].
+///
+/// The policy applies to errors of the [update](Game::update),
+/// [render](Game::render), [on_resize](Game::on_resize) and
+/// [on_exit_request](Game::on_exit_request) hooks. Errors during
+/// initialization and finalization of the loop always abort it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorPolicy {
+    /// The loop is finished and the error is returned to the caller, which
+    /// usually prints it and exits the process (See [run])
+    #[default]
+    Abort,
+    /// The error is logged and the loop continues
+    ///
+    /// Logged errors are printed to stderr, once the loop is finished and the
+    /// terminal or window has been torn down. Consecutive repetitions of the
+    /// same error are printed only once.
+    LogAndContinue,
+    /// The [on_error](Game::on_error) hook of the game decides
+    Hook,
+}
+
 /// Main game loop handler.
 ///
 /// Manages the game loop timing, state updates, and rendering.
 /// Uses a fixed timestep for updates while rendering as fast as possible.
-pub struct PixelLoop<State, CanvasImpl: RenderableCanvas> {
+pub struct PixelLoop<G, CanvasImpl: RenderableCanvas> {
     accumulator: Duration,
-    current_time: Instant,
-    last_time: Instant,
     update_timestep: Duration,
-    state: State,
+    max_updates_per_frame: u32,
+    game: G,
     input_state: CanvasImpl::Input,
     engine_state: EngineEnvironment,
     canvas: CanvasImpl,
-    update: UpdateFn<State, CanvasImpl>,
-    render: RenderFn<State, CanvasImpl>,
+    last_canvas_size: (u32, u32),
+    error_policy: ErrorPolicy,
+    logged_errors: Vec<(String, usize)>,
+    recorder: Option<Recorder>,
 }
 
-impl<State, CanvasImpl> PixelLoop<State, CanvasImpl>
+impl<G, CanvasImpl> PixelLoop<G, CanvasImpl>
 where
+    G: Game<CanvasImpl>,
     CanvasImpl: RenderableCanvas,
 {
-    /// Creates a new game loop instance.
+    /// Creates a new game loop instance driving the given [Game].
     ///
     /// # Arguments
     /// * `update_fps` - Target updates per second for the fixed timestep
-    /// * `state` - Initial game state
+    /// * `game` - The game to drive
     /// * `input_state` - Input handling implementation
     /// * `canvas` - Rendering canvas implementation
-    /// * `update` - Update function called at fixed timestep
-    /// * `render` - Render function called as often as possible
     ///
     /// # Panics
     /// If update_fps is 0
-    pub fn new(
+    pub fn from_game(
         update_fps: usize,
-        state: State,
+        game: G,
         input_state: CanvasImpl::Input,
         canvas: CanvasImpl,
-        update: UpdateFn<State, CanvasImpl>,
-        render: RenderFn<State, CanvasImpl>,
     ) -> Self {
         if update_fps == 0 {
             panic!("Designated FPS for updates needs to be > 0");
@@ -207,17 +402,19 @@ where
 
         Self {
             accumulator: Duration::default(),
-            current_time: Instant::now(),
-            last_time: Instant::now(),
             update_timestep: Duration::from_nanos(
                 (1_000_000_000f64 / update_fps as f64).round() as u64
             ),
+            max_updates_per_frame: DEFAULT_MAX_UPDATES_PER_FRAME,
             engine_state: EngineEnvironment::default(),
-            state,
+            // Zero initialized to cause an initial resize notification
+            last_canvas_size: (0, 0),
+            error_policy: ErrorPolicy::default(),
+            logged_errors: vec![],
+            recorder: None,
+            game,
             input_state,
             canvas,
-            update,
-            render,
         }
     }
 
@@ -225,57 +422,454 @@ where
     pub fn begin(&mut self) -> Result<()> {
         self.input_state.begin()?;
         self.canvas.begin()?;
+        self.game
+            .on_begin(&mut self.engine_state, &mut self.canvas)?;
         Ok(())
     }
 
-    /// Processes the next frame of the game loop.
-    pub fn next_loop(&mut self) -> Result<NextLoopState> {
-        self.last_time = self.current_time;
-        self.current_time = Instant::now();
-        let mut dt = self.current_time - self.last_time;
+    /// Replaces the engine environment used by this game loop.
+    ///
+    /// This allows to configure eg. the [Clock] before the loop is started.
+    pub fn with_environment(mut self, engine_state: EngineEnvironment) -> Self {
+        self.engine_state = engine_state;
+        self
+    }
 
-        if dt > Duration::from_millis(100) {
-            dt = Duration::from_millis(100);
+    /// Sets how errors returned by the game are handled.
+    ///
+    /// By default the loop is aborted ([ErrorPolicy::Abort]).
+    ///
+    /// # Example
+    /// ```
+    /// use pixel_loop::canvas::HeadlessCanvas;
+    /// use pixel_loop::input::HeadlessInputState;
+    /// use pixel_loop::{EngineEnvironment, ErrorPolicy, FnGame, FrameInfo, NextLoopState, PixelLoop};
+    /// use anyhow::{bail, Result};
+    ///
+    /// fn update(
+    ///     _env: &mut EngineEnvironment,
+    ///     updates: &mut u32,
+    ///     _input: &HeadlessInputState,
+    ///     _canvas: &mut HeadlessCanvas,
+    /// ) -> Result<NextLoopState> {
+    ///     *updates += 1;
+    ///     if *updates == 2 {
+    ///         bail!("level could not be loaded");
+    ///     }
+    ///     Ok(NextLoopState::Continue)
+    /// }
+    ///
+    /// fn render(
+    ///     _env: &mut EngineEnvironment,
+    ///     _updates: &mut u32,
+    ///     _input: &HeadlessInputState,
+    ///     _canvas: &mut HeadlessCanvas,
+    ///     _frame: FrameInfo,
+    /// ) -> Result<NextLoopState> {
+    ///     Ok(NextLoopState::Continue)
+    /// }
+    ///
+    /// let game = FnGame::new(0, update, render).with_error_handler(|_, _, error| {
+    ///     assert_eq!(error.to_string(), "level could not be loaded");
+    ///     Ok(NextLoopState::Exit(3))
+    /// });
+    /// let pixel_loop = PixelLoop::from_game(
+    ///     60,
+    ///     game,
+    ///     HeadlessInputState::new(),
+    ///     HeadlessCanvas::new(8, 8).with_frames(10),
+    /// )
+    /// .with_error_policy(ErrorPolicy::Hook);
+    ///
+    /// let result = HeadlessCanvas::run_frames(pixel_loop)?;
+    /// assert_eq!(result.exit_code, Some(3));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn with_error_policy(mut self, error_policy: ErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
+    /// Sets the maximal number of updates run within a single frame.
+    ///
+    /// If more time has been accumulated, eg. due to a large time scale of the
+    /// [Clock], the remaining time is dropped. This keeps the loop rendering,
+    /// instead of catching up endlessly. By default at most
+    /// [DEFAULT_MAX_UPDATES_PER_FRAME] updates are run.
+    ///
+    /// # Example
+    /// ```
+    /// use pixel_loop::canvas::HeadlessCanvas;
+    /// use pixel_loop::clock::Clock;
+    /// use pixel_loop::input::HeadlessInputState;
+    /// use pixel_loop::{EngineEnvironment, FnGame, NextLoopState, PixelLoop};
+    ///
+    /// let env = EngineEnvironment::default().with_clock(Clock::default().with_time_scale(f64::INFINITY));
+    /// let pixel_loop = PixelLoop::new(
+    ///     60,
+    ///     0u32,
+    ///     HeadlessInputState::new(),
+    ///     HeadlessCanvas::new(8, 8).with_frames(2),
+    ///     |_, updates, _, _| {
+    ///         *updates += 1;
+    ///         Ok(NextLoopState::Continue)
+    ///     },
+    ///     |_, _, _, _, _| Ok(NextLoopState::Continue),
+    /// )
+    /// .with_environment(env)
+    /// .with_max_updates_per_frame(10);
+    ///
+    /// let result = HeadlessCanvas::run_frames(pixel_loop)?.map_state(FnGame::into_state);
+    /// assert_eq!(result.state, 20);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn with_max_updates_per_frame(mut self, max_updates_per_frame: u32) -> Self {
+        self.max_updates_per_frame = max_updates_per_frame;
+        self
+    }
+
+    /// Attaches a [Recorder], which takes screenshots and records animations
+    /// of the rendered frames (See [recorder]).
+    ///
+    /// A running recording is finished, once the loop is finished.
+    pub fn with_recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Handles an error returned by the game according to the configured
+    /// [ErrorPolicy].
+    fn handle_error(&mut self, error: anyhow::Error) -> Result<NextLoopState> {
+        match self.error_policy {
+            ErrorPolicy::Abort => Err(error),
+            ErrorPolicy::LogAndContinue => {
+                let message = format!("{error:?}");
+                match self.logged_errors.last_mut() {
+                    Some((last, repetitions)) if *last == message => *repetitions += 1,
+                    _ => self.logged_errors.push((message, 1)),
+                }
+                Ok(NextLoopState::Continue)
+            }
+            ErrorPolicy::Hook => {
+                self.game
+                    .on_error(&mut self.engine_state, &mut self.canvas, error)
+            }
         }
+    }
+
+    /// Handles an exit requested by the input implementation, as well as
+    /// the hotkeys of the [Recorder], after input has been received.
+    fn handle_input(&mut self, next: NextLoopState) -> Result<NextLoopState> {
+        if let NextLoopState::Exit(..) = next {
+            return Ok(next);
+        };
 
-        while self.accumulator > self.update_timestep {
-            let next = (self.input_state).next_loop()?;
+        if let Some(request) = self.input_state.take_exit_request() {
+            let next = self.request_exit(request)?;
             if let NextLoopState::Exit(..) = next {
                 return Ok(next);
             };
+        }
+
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.handle_input(&self.input_state)?;
+        }
+        Ok(NextLoopState::Continue)
+    }
 
-            let next = (self.update)(
+    /// Processes the next frame of the game loop.
+    ///
+    /// The time advanced is determined by the [Clock] of the
+    /// [EngineEnvironment].
+    ///
+    /// Input is received once per frame at least, even if no update is run
+    /// (eg. while the clock is paused). Exit requests are therefore handled
+    /// in any case, while the input is passed on to the next update (See
+    /// [InputState::poll]).
+    ///
+    /// If the size of the canvas changed since the last frame, the
+    /// [on_resize](Game::on_resize) hook of the game is called before any
+    /// update. This includes the very first frame.
+    ///
+    /// # Example
+    /// ```
+    /// use pixel_loop::canvas::HeadlessCanvas;
+    /// use pixel_loop::input::{HeadlessInputState, KeyboardKey, KeyboardState};
+    /// use pixel_loop::{EngineEnvironment, ExitRequest, FnGame, NextLoopState, PixelLoop};
+    ///
+    /// #[derive(Default)]
+    /// struct Session {
+    ///     updates: u32,
+    ///     presses: u32,
+    /// }
+    ///
+    /// // Start paused and resume, once space has been pressed
+    /// let mut env = EngineEnvironment::default();
+    /// env.clock.pause();
+    /// let input = HeadlessInputState::new()
+    ///     .with_key_press(0, KeyboardKey::Space)
+    ///     .with_exit_request(3, ExitRequest::Interrupt);
+    /// let pixel_loop = PixelLoop::new(
+    ///     60,
+    ///     Session::default(),
+    ///     input,
+    ///     HeadlessCanvas::new(8, 8).with_frames(100),
+    ///     |_, session, input, _| {
+    ///         session.updates += 1;
+    ///         if input.is_key_pressed(KeyboardKey::Space) {
+    ///             session.presses += 1;
+    ///         }
+    ///         Ok(NextLoopState::Continue)
+    ///     },
+    ///     |env, _, input, _, _| {
+    ///         if input.is_key_pressed(KeyboardKey::Space) {
+    ///             env.clock.resume();
+    ///         }
+    ///         Ok(NextLoopState::Continue)
+    ///     },
+    /// )
+    /// .with_environment(env);
+    ///
+    /// let result = HeadlessCanvas::run_frames(pixel_loop)?.map_state(FnGame::into_state);
+    /// assert_eq!(result.exit_code, Some(130));
+    /// assert_eq!(result.frames, 5);
+    /// assert_eq!(result.state.updates, 3);
+    /// // The key pressed while being paused is passed on to the first update
+    /// assert_eq!(result.state.presses, 1);
+    ///
+    /// // Exit requests are handled, while being paused
+    /// let mut env = EngineEnvironment::default();
+    /// env.clock.pause();
+    /// let input = HeadlessInputState::new().with_exit_request(0, ExitRequest::Interrupt);
+    /// let pixel_loop = PixelLoop::new(
+    ///     60,
+    ///     Session::default(),
+    ///     input,
+    ///     HeadlessCanvas::new(8, 8).with_frames(100),
+    ///     |_, session, _, _| {
+    ///         session.updates += 1;
+    ///         Ok(NextLoopState::Continue)
+    ///     },
+    ///     |_, _, _, _, _| Ok(NextLoopState::Continue),
+    /// )
+    /// .with_environment(env);
+    ///
+    /// let result = HeadlessCanvas::run_frames(pixel_loop)?.map_state(FnGame::into_state);
+    /// assert_eq!(result.exit_code, Some(130));
+    /// assert_eq!(result.frames, 1);
+    /// assert_eq!(result.state.updates, 0);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn next_loop(&mut self) -> Result<NextLoopState> {
+        let canvas_size = (self.canvas.width(), self.canvas.height());
+        let resized = canvas_size != self.last_canvas_size;
+        if resized {
+            let old_canvas_size = self.last_canvas_size;
+            self.last_canvas_size = canvas_size;
+            if let Err(error) = self.game.on_resize(
                 &mut self.engine_state,
-                &mut self.state,
-                &self.input_state,
                 &mut self.canvas,
-            )?;
+                old_canvas_size,
+                canvas_size,
+            ) {
+                let next = self.handle_error(error)?;
+                if let NextLoopState::Exit(..) = next {
+                    return Ok(next);
+                };
+            }
+        }
+
+        let dt = self.engine_state.clock.tick(self.update_timestep);
+        self.accumulator = self.accumulator.saturating_add(dt);
+
+        if self.accumulator < self.update_timestep {
+            let next = self.input_state.poll()?;
+            let next = self.handle_input(next)?;
+            if let NextLoopState::Exit(..) = next {
+                return Ok(next);
+            };
+        }
+
+        let mut updates = 0;
+        while self.accumulator >= self.update_timestep {
+            if updates >= self.max_updates_per_frame {
+                self.accumulator = Duration::ZERO;
+                break;
+            }
+
+            let next = self.input_state.next_loop()?;
+            let next = self.handle_input(next)?;
+            if let NextLoopState::Exit(..) = next {
+                return Ok(next);
+            };
+
+            let next =
+                match self
+                    .game
+                    .update(&mut self.engine_state, &self.input_state, &mut self.canvas)
+                {
+                    Ok(next) => next,
+                    Err(error) => self.handle_error(error)?,
+                };
             if let NextLoopState::Exit(..) = next {
                 return Ok(next);
             };
             self.accumulator -= self.update_timestep;
+            updates += 1;
         }
 
-        let next = (self.render)(
+        let frame = FrameInfo {
+            dt,
+            alpha: self.accumulator.as_secs_f64() / self.update_timestep.as_secs_f64(),
+            updates,
+            resized,
+        };
+        let next = match self.game.render(
             &mut self.engine_state,
-            &mut self.state,
             &self.input_state,
             &mut self.canvas,
-            dt,
-        )?;
+            frame,
+        ) {
+            Ok(next) => next,
+            Err(error) => self.handle_error(error)?,
+        };
+
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder
+                .capture(&self.canvas, dt)
+                .context("capture frame for recorder")?;
+        }
+
         if let NextLoopState::Exit(..) = next {
             return Ok(next);
         };
 
-        self.accumulator += dt;
         Ok(NextLoopState::Continue)
     }
 
+    /// Asks the game, whether the loop should be exited due to the given
+    /// request (See [on_exit_request](Game::on_exit_request)).
+    ///
+    /// Requests received by the input implementation are handled by
+    /// [next_loop](PixelLoop::next_loop) already. Canvas implementations use
+    /// this for requests they receive themselves, eg. closing the window.
+    pub fn request_exit(&mut self, request: ExitRequest) -> Result<NextLoopState> {
+        match self
+            .game
+            .on_exit_request(&mut self.engine_state, &mut self.canvas, request)
+        {
+            Ok(next) => Ok(next),
+            Err(error) => self.handle_error(error),
+        }
+    }
+
     /// Cleans up resources when the game loop ends.
+    ///
+    /// In contrast to earlier versions this does not exit the process anymore.
+    /// Exiting is left to the caller (eg. [run]).
+    ///
+    /// Errors logged due to [ErrorPolicy::LogAndContinue] are printed to
+    /// stderr afterwards.
     pub fn finish(&mut self, code: i32) -> Result<()> {
-        self.input_state.finish()?;
-        self.canvas.finish(code)?;
-        std::process::exit(code);
+        let result = (|| -> Result<()> {
+            self.finish_recorder()?;
+            self.game.on_exit(&mut self.engine_state, code)?;
+            self.input_state.finish()?;
+            self.canvas.finish(code)?;
+            Ok(())
+        })();
+        self.print_logged_errors();
+        result
+    }
+
+    /// Prints the errors logged due to [ErrorPolicy::LogAndContinue].
+    fn print_logged_errors(&mut self) {
+        for (message, repetitions) in self.logged_errors.drain(..) {
+            eprintln!("Error: {message}");
+            if repetitions > 1 {
+                eprintln!("(repeated {repetitions} times)");
+            }
+        }
+    }
+
+    /// Stops a running recording of the [Recorder], if any.
+    pub(crate) fn finish_recorder(&mut self) -> Result<()> {
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.stop()?;
+        }
+        Ok(())
+    }
+
+    /// Cleans up resources after the loop has been aborted due to the given
+    /// error, which is returned afterwards.
+    ///
+    /// The loop is finished with the [ERROR_EXIT_CODE]. If finishing fails as
+    /// well, this is attached to the returned error. Canvas implementations
+    /// use this to tear down the terminal or window, before the error is
+    /// reported.
+    pub fn abort(&mut self, error: anyhow::Error) -> anyhow::Error {
+        match self.finish(ERROR_EXIT_CODE) {
+            Ok(()) => error,
+            Err(finish_error) => error.context(format!(
+                "finishing the aborted loop failed: {finish_error:#}"
+            )),
+        }
+    }
+
+    /// Consumes the game loop and returns the owned game.
+    pub fn into_game(self) -> G {
+        self.game
+    }
+}
+
+impl<State, U, R, CanvasImpl> PixelLoop<FnGame<State, U, R>, CanvasImpl>
+where
+    CanvasImpl: RenderableCanvas,
+    U: FnMut(
+        &mut EngineEnvironment,
+        &mut State,
+        &CanvasImpl::Input,
+        &mut CanvasImpl,
+    ) -> Result<NextLoopState>,
+    R: FnMut(
+        &mut EngineEnvironment,
+        &mut State,
+        &CanvasImpl::Input,
+        &mut CanvasImpl,
+        FrameInfo,
+    ) -> Result<NextLoopState>,
+{
+    /// Creates a new game loop instance.
+    ///
+    /// # Arguments
+    /// * `update_fps` - Target updates per second for the fixed timestep
+    /// * `state` - Initial game state
+    /// * `input_state` - Input handling implementation
+    /// * `canvas` - Rendering canvas implementation
+    /// * `update` - Update function called at fixed timestep
+    /// * `render` - Render function called as often as possible
+    ///
+    /// # Panics
+    /// If update_fps is 0
+    pub fn new(
+        update_fps: usize,
+        state: State,
+        input_state: CanvasImpl::Input,
+        canvas: CanvasImpl,
+        update: U,
+        render: R,
+    ) -> Self {
+        Self::from_game(
+            update_fps,
+            FnGame::new(state, update, render),
+            input_state,
+            canvas,
+        )
+    }
+
+    /// Consumes the game loop and returns the owned game state.
+    pub fn into_state(self) -> State {
+        self.game.into_state()
     }
 }
 
@@ -291,20 +885,204 @@ where
 ///
 /// # Errors
 /// Returns an error if initialization fails or if any update/render call fails
-pub fn run<State: 'static, CanvasImpl: RenderableCanvas + 'static>(
+pub fn run<State, CanvasImpl, U, R>(
+    updates_per_second: usize,
+    state: State,
+    input_state: CanvasImpl::Input,
+    canvas: CanvasImpl,
+    update: U,
+    render: R,
+) -> !
+where
+    State: 'static,
+    CanvasImpl: RenderableCanvas + 'static,
+    U: FnMut(
+            &mut EngineEnvironment,
+            &mut State,
+            &CanvasImpl::Input,
+            &mut CanvasImpl,
+        ) -> Result<NextLoopState>
+        + 'static,
+    R: FnMut(
+            &mut EngineEnvironment,
+            &mut State,
+            &CanvasImpl::Input,
+            &mut CanvasImpl,
+            FrameInfo,
+        ) -> Result<NextLoopState>
+        + 'static,
+{
+    run_game(
+        updates_per_second,
+        FnGame::new(state, update, render),
+        input_state,
+        canvas,
+    )
+}
+
+/// Runs the game loop with the provided state and implementations and returns
+/// once the loop has been exited.
+///
+/// In contrast to [run] the process is not exited. Instead the exit code and
+/// the final game state are returned to the caller. All destructors are run as
+/// usual, and multiple loops may be run one after another in the same process,
+/// as long as the used canvas implementation supports it.
+///
+/// # Arguments
+/// * `updates_per_second` - Target rate for fixed timestep updates
+/// * `state` - Initial game state
+/// * `input_state` - Input handling implementation
+/// * `canvas` - Rendering canvas implementation
+/// * `update` - Update function called at fixed timestep
+/// * `render` - Render function called as often as possible
+///
+/// # Returns
+/// * `(i32, State)` - The exit code and the final game state
+///
+/// # Errors
+/// Returns an error if initialization fails or if any update/render call fails
+pub fn run_to_completion<State, CanvasImpl, U, R>(
     updates_per_second: usize,
     state: State,
     input_state: CanvasImpl::Input,
     canvas: CanvasImpl,
-    update: UpdateFn<State, CanvasImpl>,
-    render: RenderFn<State, CanvasImpl>,
-) -> ! {
-    CanvasImpl::run(PixelLoop::new(
+    update: U,
+    render: R,
+) -> Result<(i32, State)>
+where
+    State: 'static,
+    CanvasImpl: RenderableCanvas + 'static,
+    U: FnMut(
+            &mut EngineEnvironment,
+            &mut State,
+            &CanvasImpl::Input,
+            &mut CanvasImpl,
+        ) -> Result<NextLoopState>
+        + 'static,
+    R: FnMut(
+            &mut EngineEnvironment,
+            &mut State,
+            &CanvasImpl::Input,
+            &mut CanvasImpl,
+            FrameInfo,
+        ) -> Result<NextLoopState>
+        + 'static,
+{
+    let (code, game) = run_game_to_completion(
+        updates_per_second,
+        FnGame::new(state, update, render),
+        input_state,
+        canvas,
+    )?;
+    Ok((code, game.into_state()))
+}
+
+/// Runs the game loop driving the provided [Game] implementation.
+///
+/// # Arguments
+/// * `updates_per_second` - Target rate for fixed timestep updates
+/// * `game` - The game to drive
+/// * `input_state` - Input handling implementation
+/// * `canvas` - Rendering canvas implementation
+///
+/// # Errors
+/// Returns an error if initialization fails or if any update/render call fails
+pub fn run_game<G, CanvasImpl>(
+    updates_per_second: usize,
+    game: G,
+    input_state: CanvasImpl::Input,
+    canvas: CanvasImpl,
+) -> !
+where
+    G: Game<CanvasImpl> + 'static,
+    CanvasImpl: RenderableCanvas + 'static,
+{
+    CanvasImpl::run(PixelLoop::from_game(
+        updates_per_second,
+        game,
+        input_state,
+        canvas,
+    ))
+}
+
+/// Runs the game loop driving the provided [Game] implementation and returns
+/// once the loop has been exited.
+///
+/// See [run_to_completion] for details.
+///
+/// # Returns
+/// * `(i32, G)` - The exit code and the final game
+///
+/// # Errors
+/// Returns an error if initialization fails or if any update/render call fails
+pub fn run_game_to_completion<G, CanvasImpl>(
+    updates_per_second: usize,
+    game: G,
+    input_state: CanvasImpl::Input,
+    canvas: CanvasImpl,
+) -> Result<(i32, G)>
+where
+    G: Game<CanvasImpl> + 'static,
+    CanvasImpl: RenderableCanvas + 'static,
+{
+    CanvasImpl::run_to_completion(PixelLoop::from_game(
+        updates_per_second,
+        game,
+        input_state,
+        canvas,
+    ))
+}
+
+/// Runs the game loop on a [HeadlessCanvas](canvas::HeadlessCanvas) and
+/// returns the outcome of the run, instead of exiting the process.
+///
+/// The loop is driven by a virtual clock for the number of frames configured
+/// on the canvas, which makes it suitable to exercise update and render
+/// functions within tests.
+///
+/// # Arguments
+/// * `updates_per_second` - Target rate for fixed timestep updates
+/// * `state` - Initial game state
+/// * `input_state` - Input implementation, eg. a scripted
+///   [HeadlessInputState](input::HeadlessInputState) or a
+///   [ReplayInputState](input::ReplayInputState)
+/// * `canvas` - Headless canvas implementation
+/// * `update` - Update function called at fixed timestep
+/// * `render` - Render function called once per frame
+///
+/// # Errors
+/// Returns an error if initialization fails or if any update/render call fails
+pub fn run_headless<State, I, U, R>(
+    updates_per_second: usize,
+    state: State,
+    input_state: I,
+    canvas: canvas::HeadlessCanvas<I>,
+    update: U,
+    render: R,
+) -> Result<canvas::headless::HeadlessResult<State>>
+where
+    I: input::InputState,
+    U: FnMut(
+        &mut EngineEnvironment,
+        &mut State,
+        &I,
+        &mut canvas::HeadlessCanvas<I>,
+    ) -> Result<NextLoopState>,
+    R: FnMut(
+        &mut EngineEnvironment,
+        &mut State,
+        &I,
+        &mut canvas::HeadlessCanvas<I>,
+        FrameInfo,
+    ) -> Result<NextLoopState>,
+{
+    let result = canvas::HeadlessCanvas::run_frames(PixelLoop::new(
         updates_per_second,
         state,
         input_state,
         canvas,
         update,
         render,
-    ))
+    ))?;
+    Ok(result.map_state(FnGame::into_state))
 }
@@ -0,0 +1,493 @@
+//! Screenshots and animated recordings of running loops.
+//!
+//! A [Recorder] is attached to a [PixelLoop](crate::PixelLoop) using
+//! [with_recorder](crate::PixelLoop::with_recorder). It captures the contents
+//! of the canvas after each call to the render function of the game, which
+//! works the same for every canvas implementation, including the
+//! [HeadlessCanvas](crate::canvas::HeadlessCanvas).
+//!
+//! Recordings are toggled at runtime using a configurable key (`F10` by
+//! default) and written as animated GIF (`gif` feature) or APNG (`png`
+//! feature). The duration of every frame is taken from the time elapsed
+//! between rendered frames ([FrameInfo::dt](crate::FrameInfo::dt)). Another
+//! key (`F12` by default) saves a screenshot of the next rendered frame as PNG,
+//! or as PPM if the `png` feature is disabled.
+//!
+//! All files are written to the configured directory, named
+//! `recording-0001.gif`, `screenshot-0001.png` and so on, without overwriting
+//! existing files.
+//!
+//! # Example
+//! ```
+//! use pixel_loop::canvas::{Canvas, HeadlessCanvas, RenderableCanvas};
+//! use pixel_loop::color::Color;
+//! use pixel_loop::input::{HeadlessInputState, KeyboardKey};
+//! use pixel_loop::recorder::{Recorder, RecordingFormat};
+//! use pixel_loop::{EngineEnvironment, FrameInfo, NextLoopState, PixelLoop};
+//! use anyhow::Result;
+//!
+//! fn update(
+//!     _env: &mut EngineEnvironment,
+//!     x: &mut i64,
+//!     _input: &HeadlessInputState,
+//!     _canvas: &mut HeadlessCanvas,
+//! ) -> Result<NextLoopState> {
+//!     *x += 1;
+//!     Ok(NextLoopState::Continue)
+//! }
+//!
+//! fn render(
+//!     _env: &mut EngineEnvironment,
+//!     x: &mut i64,
+//!     _input: &HeadlessInputState,
+//!     canvas: &mut HeadlessCanvas,
+//!     _frame: FrameInfo,
+//! ) -> Result<NextLoopState> {
+//!     canvas.clear_screen(&Color::from_rgb(0, 0, 0));
+//!     canvas.filled_rect(*x, 4, 4, 4, &Color::from_rgb(255, 0, 0));
+//!     canvas.render()?;
+//!     Ok(NextLoopState::Continue)
+//! }
+//!
+//! let directory = std::env::temp_dir().join("pixel_loop_recorder_example");
+//! # let _ = std::fs::remove_dir_all(&directory);
+//! let recorder = Recorder::new(&directory).with_format(RecordingFormat::Gif);
+//!
+//! // Start recording after 5 updates and stop it after 20 further ones
+//! let input = HeadlessInputState::new()
+//!     .with_key_tap(5, KeyboardKey::F10)
+//!     .with_key_tap(25, KeyboardKey::F10);
+//! let canvas = HeadlessCanvas::new(32, 16).with_frames(40);
+//! let result = HeadlessCanvas::run_frames(
+//!     PixelLoop::new(60, 0, input, canvas, update, render).with_recorder(recorder),
+//! )?;
+//!
+//! let files = result.recorder.expect("recorder").files().to_vec();
+//! assert_eq!(files, vec![directory.join("recording-0001.gif")]);
+//! # std::fs::remove_dir_all(&directory)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+// Without any animation format enabled, recordings can not be started, which
+// renders most of their implementation unreachable.
+#![cfg_attr(
+    not(any(feature = "gif", feature = "png")),
+    allow(dead_code, unreachable_code, unused_variables)
+)]
+
+use crate::canvas::Canvas;
+use crate::color::Color;
+use crate::input::{KeyboardKey, KeyboardState};
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[cfg(any(feature = "gif", feature = "png"))]
+use std::fs::File;
+#[cfg(any(feature = "gif", feature = "png"))]
+use std::io::BufWriter;
+
+/// File formats of animated recordings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RecordingFormat {
+    /// Animated GIF with a palette of up to 256 colors per frame, which is
+    /// written while recording.
+    ///
+    /// Only available if the `gif` feature is enabled.
+    #[default]
+    Gif,
+    /// Animated PNG in full color, including alpha. As the number of frames
+    /// needs to be known up front, all frames are kept in memory until the
+    /// recording is stopped.
+    ///
+    /// Only available if the `png` feature is enabled.
+    Apng,
+}
+
+impl RecordingFormat {
+    /// File extension used for recordings in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RecordingFormat::Gif => "gif",
+            RecordingFormat::Apng => "png",
+        }
+    }
+
+    /// Frames shown shorter than this are merged with the following one, as
+    /// the format (or the common viewers) can not display them properly.
+    fn min_frame_time(&self) -> Duration {
+        self.frame_time_unit().max(match self {
+            // Browsers show GIF frames with a delay of less than 20ms for
+            // 100ms instead.
+            RecordingFormat::Gif => Duration::from_millis(20),
+            RecordingFormat::Apng => Duration::ZERO,
+        })
+    }
+
+    /// Smallest unit of frame times supported by the format.
+    fn frame_time_unit(&self) -> Duration {
+        match self {
+            RecordingFormat::Gif => Duration::from_millis(10),
+            RecordingFormat::Apng => Duration::from_millis(1),
+        }
+    }
+}
+
+/// Encoder of a running recording.
+enum RecordingEncoder {
+    #[cfg(feature = "gif")]
+    Gif(gif::Encoder<BufWriter<File>>),
+    #[cfg(feature = "png")]
+    Apng(Vec<(Vec<Color>, u16)>),
+}
+
+/// A running recording.
+struct Recording {
+    path: PathBuf,
+    format: RecordingFormat,
+    width: u32,
+    height: u32,
+    encoder: RecordingEncoder,
+    // The last captured frame, waiting for the next one to know how long it
+    // is shown.
+    pending: Option<(Vec<Color>, Duration)>,
+    last_dt: Duration,
+    // Part of the frame time not representable by the format, which is
+    // carried over to the next frame to avoid drift.
+    carry: Duration,
+}
+
+impl Recording {
+    fn create(path: PathBuf, format: RecordingFormat, width: u32, height: u32) -> Result<Self> {
+        let encoder = match format {
+            #[cfg(feature = "gif")]
+            RecordingFormat::Gif => {
+                if width > u16::MAX as u32 || height > u16::MAX as u32 {
+                    bail!("Canvas of {width}x{height} is too large to be recorded as GIF");
+                }
+                let file = File::create(&path)
+                    .with_context(|| format!("create recording {}", path.display()))?;
+                let mut encoder =
+                    gif::Encoder::new(BufWriter::new(file), width as u16, height as u16, &[])?;
+                encoder.set_repeat(gif::Repeat::Infinite)?;
+                RecordingEncoder::Gif(encoder)
+            }
+            #[cfg(feature = "png")]
+            RecordingFormat::Apng => RecordingEncoder::Apng(vec![]),
+            #[allow(unreachable_patterns)]
+            format => bail!(
+                "Recording as {format:?} is not supported, as the {} feature is disabled",
+                match format {
+                    RecordingFormat::Gif => "gif",
+                    RecordingFormat::Apng => "png",
+                }
+            ),
+        };
+
+        Ok(Self {
+            path,
+            format,
+            width,
+            height,
+            encoder,
+            pending: None,
+            last_dt: Duration::ZERO,
+            carry: Duration::ZERO,
+        })
+    }
+
+    fn push(&mut self, pixels: &[Color], dt: Duration) -> Result<()> {
+        self.last_dt = dt;
+        match self.pending.take() {
+            Some((previous, duration)) if duration + dt >= self.format.min_frame_time() => {
+                self.write_frame(previous, duration + dt)?;
+                self.pending = Some((pixels.to_vec(), Duration::ZERO));
+            }
+            // Frames shown too short are replaced by the next one, which
+            // takes over their time.
+            Some((_, duration)) => self.pending = Some((pixels.to_vec(), duration + dt)),
+            None => self.pending = Some((pixels.to_vec(), Duration::ZERO)),
+        }
+        Ok(())
+    }
+
+    fn write_frame(&mut self, pixels: Vec<Color>, duration: Duration) -> Result<()> {
+        let unit = self.format.frame_time_unit();
+        let duration = duration + self.carry;
+        let delay = (duration.as_nanos() / unit.as_nanos()).min(u16::MAX as u128) as u16;
+        self.carry = duration.saturating_sub(unit * delay as u32);
+
+        match self.encoder {
+            #[cfg(feature = "gif")]
+            RecordingEncoder::Gif(ref mut encoder) => {
+                use crate::color::ColorAsByteSlice;
+
+                let mut rgba = pixels.as_byte_slice().to_vec();
+                let mut frame = gif::Frame::from_rgba_speed(
+                    self.width as u16,
+                    self.height as u16,
+                    &mut rgba,
+                    10,
+                );
+                frame.delay = delay;
+                encoder.write_frame(&frame)?;
+            }
+            #[cfg(feature = "png")]
+            RecordingEncoder::Apng(ref mut frames) => frames.push((pixels, delay)),
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<PathBuf> {
+        if let Some((pixels, duration)) = self.pending.take() {
+            // Nothing follows the last frame, therefore it is shown as long as
+            // the frame before.
+            let duration = duration.max(self.last_dt).max(self.format.min_frame_time());
+            self.write_frame(pixels, duration)?;
+        }
+
+        match self.encoder {
+            #[cfg(feature = "gif")]
+            RecordingEncoder::Gif(encoder) => {
+                use std::io::Write;
+                encoder.into_inner()?.flush()?;
+            }
+            #[cfg(feature = "png")]
+            RecordingEncoder::Apng(frames) => {
+                use crate::color::ColorAsByteSlice;
+
+                let file = File::create(&self.path)
+                    .with_context(|| format!("create recording {}", self.path.display()))?;
+                let mut encoder = png::Encoder::new(BufWriter::new(file), self.width, self.height);
+                encoder.set_color(png::ColorType::Rgba);
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder.set_animated(frames.len().max(1) as u32, 0)?;
+                let mut writer = encoder.write_header()?;
+                for (pixels, delay) in &frames {
+                    writer.set_frame_delay(*delay, 1000)?;
+                    writer.write_image_data(pixels.as_byte_slice())?;
+                }
+                if frames.is_empty() {
+                    let empty = vec![
+                        Color::from_rgba(0, 0, 0, 0);
+                        self.width as usize * self.height as usize
+                    ];
+                    writer.write_image_data(empty.as_byte_slice())?;
+                }
+                writer.finish()?;
+            }
+        }
+        Ok(self.path)
+    }
+}
+
+/// Records screenshots and animations of a running loop.
+///
+/// See the [module documentation](crate::recorder) for details.
+pub struct Recorder {
+    directory: PathBuf,
+    format: RecordingFormat,
+    toggle_key: Option<KeyboardKey>,
+    screenshot_key: Option<KeyboardKey>,
+    toggle_key_pressed: bool,
+    screenshot_key_pressed: bool,
+    recording: Option<Recording>,
+    recording_requested: bool,
+    screenshot_requested: bool,
+    files: Vec<PathBuf>,
+}
+
+impl Recorder {
+    /// Creates a new recorder writing its files to the given directory, which
+    /// is created if needed.
+    pub fn new<P: AsRef<Path>>(directory: P) -> Self {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+            format: RecordingFormat::default(),
+            toggle_key: Some(KeyboardKey::F10),
+            screenshot_key: Some(KeyboardKey::F12),
+            toggle_key_pressed: false,
+            screenshot_key_pressed: false,
+            recording: None,
+            recording_requested: false,
+            screenshot_requested: false,
+            files: vec![],
+        }
+    }
+
+    /// Sets the file format of recordings.
+    ///
+    /// Defaults to [RecordingFormat::Gif].
+    pub fn with_format(mut self, format: RecordingFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets the key starting and stopping a recording, or disables it.
+    ///
+    /// Defaults to `F10`.
+    pub fn with_toggle_key(mut self, key: Option<KeyboardKey>) -> Self {
+        self.toggle_key = key;
+        self
+    }
+
+    /// Sets the key saving a screenshot, or disables it.
+    ///
+    /// Defaults to `F12`.
+    pub fn with_screenshot_key(mut self, key: Option<KeyboardKey>) -> Self {
+        self.screenshot_key = key;
+        self
+    }
+
+    /// Starts recording with the first rendered frame.
+    pub fn with_recording(mut self, recording: bool) -> Self {
+        self.recording_requested = recording;
+        self
+    }
+
+    /// Get the directory files are written to.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Check if a recording is running, or will be started with the next
+    /// rendered frame.
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some() || self.recording_requested
+    }
+
+    /// Get the paths of all screenshots and finished recordings written so
+    /// far.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Starts a recording with the next rendered frame, if none is running.
+    pub fn start(&mut self) {
+        if self.recording.is_none() {
+            self.recording_requested = true;
+        }
+    }
+
+    /// Stops the running recording and writes it to disk.
+    ///
+    /// # Returns
+    /// * `Some(PathBuf)` - Path of the finished recording
+    /// * `None` - If no recording was running
+    pub fn stop(&mut self) -> Result<Option<PathBuf>> {
+        self.recording_requested = false;
+        match self.recording.take() {
+            Some(recording) => {
+                let path = recording.finish().context("finish recording")?;
+                self.files.push(path.clone());
+                Ok(Some(path))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Saves a screenshot of the next rendered frame.
+    pub fn screenshot(&mut self) {
+        self.screenshot_requested = true;
+    }
+
+    /// Starts or stops recordings and takes screenshots, if the configured
+    /// keys have been pressed.
+    ///
+    /// This is called by the [PixelLoop](crate::PixelLoop) whenever input has
+    /// been received. A key press, which is still reported from the last call
+    /// (eg. while the clock is paused), is only handled once.
+    pub fn handle_input<I: KeyboardState>(&mut self, input: &I) -> Result<()> {
+        let toggle_key_pressed = self.toggle_key.is_some_and(|key| input.is_key_pressed(key));
+        if toggle_key_pressed && !self.toggle_key_pressed {
+            if self.is_recording() {
+                self.stop()?;
+            } else {
+                self.start();
+            }
+        }
+        self.toggle_key_pressed = toggle_key_pressed;
+
+        let screenshot_key_pressed = self
+            .screenshot_key
+            .is_some_and(|key| input.is_key_pressed(key));
+        if screenshot_key_pressed && !self.screenshot_key_pressed {
+            self.screenshot();
+        }
+        self.screenshot_key_pressed = screenshot_key_pressed;
+        Ok(())
+    }
+
+    /// Captures the contents of the canvas after a frame has been rendered.
+    ///
+    /// This is called by the [PixelLoop](crate::PixelLoop) after each call to
+    /// the render function of the game.
+    ///
+    /// # Arguments
+    /// * `canvas` - The canvas holding the rendered frame
+    /// * `dt` - The time elapsed since the last rendered frame
+    pub fn capture<C: Canvas>(&mut self, canvas: &C, dt: Duration) -> Result<()> {
+        let (width, height) = (canvas.width(), canvas.height());
+
+        if self.screenshot_requested {
+            self.screenshot_requested = false;
+            self.save_screenshot(canvas)?;
+        }
+
+        // Frames of different sizes can not be combined in one animation,
+        // therefore a new recording is started once the canvas is resized.
+        if let Some(recording) = &self.recording {
+            if (recording.width, recording.height) != (width, height) {
+                self.stop()?;
+                self.recording_requested = true;
+            }
+        }
+
+        if self.recording_requested {
+            self.recording_requested = false;
+            let path = self.next_path("recording", self.format.extension())?;
+            self.recording = Some(Recording::create(path, self.format, width, height)?);
+        }
+
+        if let Some(recording) = &mut self.recording {
+            let pixels = canvas.get_range(0..(width * height) as usize);
+            recording.push(pixels, dt)?;
+        }
+        Ok(())
+    }
+
+    fn save_screenshot<C: Canvas>(&mut self, canvas: &C) -> Result<()> {
+        #[cfg(feature = "png")]
+        let path = {
+            let path = self.next_path("screenshot", "png")?;
+            canvas.save_png(&path)?;
+            path
+        };
+        #[cfg(not(feature = "png"))]
+        let path = {
+            let path = self.next_path("screenshot", "ppm")?;
+            canvas.save_ppm(&path)?;
+            path
+        };
+
+        self.files.push(path);
+        Ok(())
+    }
+
+    /// Finds the first numbered file with the given prefix and extension,
+    /// which does not exist yet.
+    fn next_path(&self, prefix: &str, extension: &str) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.directory)
+            .with_context(|| format!("create recording directory {}", self.directory.display()))?;
+
+        for number in 1.. {
+            let path = self
+                .directory
+                .join(format!("{prefix}-{number:04}.{extension}"));
+            if !path.exists() && !self.files.contains(&path) {
+                return Ok(path);
+            }
+        }
+        unreachable!("to find an unused file name");
+    }
+}
@@ -0,0 +1,459 @@
+//! Sprite sheets and frame based animations.
+//!
+//! This module builds upon the [InMemoryCanvas] to store multiple sprites
+//! (frames) within one image, a so called sprite sheet. Frames are either laid
+//! out in a regular grid ([SpriteSheet::from_grid]) or described by explicit
+//! rectangles ([SpriteSheet::from_rects]).
+//!
+//! An [Animation] cycles through a list of frames of a sprite sheet, each one
+//! shown for a certain duration. It is advanced by the time elapsed between
+//! rendered frames, which is provided by the loop as
+//! [FrameInfo::dt](crate::FrameInfo::dt). The [draw_sprite] helper advances an
+//! animation and draws its current frame in one go.
+//!
+//! # Example
+//! ```
+//! use pixel_loop::canvas::{Canvas, InMemoryCanvas};
+//! use pixel_loop::color::Color;
+//! use pixel_loop::sprite::{draw_sprite, Animation, AnimationMode, Flip, SpriteSheet};
+//! use std::time::Duration;
+//!
+//! let red = Color::from_rgb(255, 0, 0);
+//! let blue = Color::from_rgb(0, 0, 255);
+//!
+//! // Sheet of two 2x2 frames: the first one red, the second one blue
+//! let mut image = InMemoryCanvas::new(4, 2, &red);
+//! image.filled_rect(2, 0, 2, 2, &blue);
+//! let sheet = SpriteSheet::from_grid(image, 2, 2)?;
+//!
+//! let mut walk = Animation::new([0, 1], Duration::from_millis(100)).with_mode(AnimationMode::Once);
+//! let mut screen = InMemoryCanvas::new(8, 8, &Color::from_rgb(0, 0, 0));
+//!
+//! draw_sprite(&mut screen, &sheet, &mut walk, Duration::from_millis(50), 1, 1, Flip::None);
+//! assert_eq!(screen.get(1, 1), &red);
+//!
+//! draw_sprite(&mut screen, &sheet, &mut walk, Duration::from_millis(150), 1, 1, Flip::None);
+//! assert_eq!(screen.get(1, 1), &blue);
+//! assert!(walk.is_finished());
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::canvas::{Canvas, InMemoryCanvas};
+use crate::color::BlendMode;
+use anyhow::{bail, Result};
+use std::time::Duration;
+
+/// A rectangular region of a sprite sheet in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpriteRect {
+    /// Left edge of the region
+    pub x: u32,
+    /// Top edge of the region
+    pub y: u32,
+    /// Width of the region
+    pub width: u32,
+    /// Height of the region
+    pub height: u32,
+}
+
+impl SpriteRect {
+    /// Creates a new SpriteRect at the given position with the given size.
+    pub const fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// Mirroring applied to a sprite when it is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Flip {
+    /// Draw the sprite as is
+    #[default]
+    None,
+    /// Mirror the sprite along its vertical axis (left <-> right)
+    Horizontal,
+    /// Mirror the sprite along its horizontal axis (top <-> bottom)
+    Vertical,
+    /// Mirror the sprite along both axes, which equals rotating it by 180°
+    Both,
+}
+
+impl Flip {
+    fn is_horizontal(self) -> bool {
+        matches!(self, Flip::Horizontal | Flip::Both)
+    }
+
+    fn is_vertical(self) -> bool {
+        matches!(self, Flip::Vertical | Flip::Both)
+    }
+}
+
+/// An image containing multiple sprites (frames).
+///
+/// Frames are addressed by their index, in the order they have been defined.
+/// They are drawn using [BlendMode::Alpha] by default, so transparent pixels
+/// of the image keep the background visible.
+#[derive(Clone)]
+pub struct SpriteSheet {
+    canvas: InMemoryCanvas,
+    frames: Vec<SpriteRect>,
+    blend: BlendMode,
+}
+
+impl SpriteSheet {
+    /// Creates a sprite sheet, whose frames are laid out in a regular grid of
+    /// equally sized cells.
+    ///
+    /// Frames are indexed row by row, from left to right. Pixels at the right
+    /// and bottom edges of the image, which do not fill a whole cell, are
+    /// ignored.
+    ///
+    /// # Errors
+    /// Returns an error if the frame size is zero or exceeds the size of the
+    /// image.
+    pub fn from_grid(canvas: InMemoryCanvas, frame_width: u32, frame_height: u32) -> Result<Self> {
+        if frame_width == 0 || frame_height == 0 {
+            bail!("Sprite frames need to be at least 1x1 pixels, got {frame_width}x{frame_height}");
+        }
+        let columns = canvas.width() / frame_width;
+        let rows = canvas.height() / frame_height;
+        if columns == 0 || rows == 0 {
+            bail!(
+                "Sprite frames of {frame_width}x{frame_height} do not fit into sheet of {}x{}",
+                canvas.width(),
+                canvas.height()
+            );
+        }
+
+        let frames = (0..rows)
+            .flat_map(|row| {
+                (0..columns).map(move |column| {
+                    SpriteRect::new(
+                        column * frame_width,
+                        row * frame_height,
+                        frame_width,
+                        frame_height,
+                    )
+                })
+            })
+            .collect();
+        Ok(Self {
+            canvas,
+            frames,
+            blend: BlendMode::Alpha,
+        })
+    }
+
+    /// Creates a sprite sheet with explicitly defined frames.
+    ///
+    /// # Errors
+    /// Returns an error if any of the frames is empty or reaches beyond the
+    /// bounds of the image.
+    pub fn from_rects(canvas: InMemoryCanvas, frames: Vec<SpriteRect>) -> Result<Self> {
+        for (index, frame) in frames.iter().enumerate() {
+            if frame.width == 0 || frame.height == 0 {
+                bail!("Sprite frame {index} is empty: {frame:?}");
+            }
+            if frame.x as u64 + frame.width as u64 > canvas.width() as u64
+                || frame.y as u64 + frame.height as u64 > canvas.height() as u64
+            {
+                bail!(
+                    "Sprite frame {index} is out of bounds of sheet of {}x{}: {frame:?}",
+                    canvas.width(),
+                    canvas.height()
+                );
+            }
+        }
+        Ok(Self {
+            canvas,
+            frames,
+            blend: BlendMode::Alpha,
+        })
+    }
+
+    /// Sets how frames are combined with the pixels of the canvas they are
+    /// drawn onto.
+    ///
+    /// Defaults to [BlendMode::Alpha].
+    pub fn with_blend_mode(mut self, blend: BlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    /// Get how frames are combined with the pixels of the canvas they are
+    /// drawn onto.
+    pub fn blend_mode(&self) -> BlendMode {
+        self.blend
+    }
+
+    /// Get the image the frames are taken from.
+    pub fn canvas(&self) -> &InMemoryCanvas {
+        &self.canvas
+    }
+
+    /// Get the region of the frame with the given index, if it exists.
+    pub fn frame(&self, index: usize) -> Option<SpriteRect> {
+        self.frames.get(index).copied()
+    }
+
+    /// Get the regions of all frames of the sheet.
+    pub fn frames(&self) -> &[SpriteRect] {
+        &self.frames
+    }
+
+    /// The number of frames of the sheet.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Check if the sheet does not contain any frames.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Draw the frame with the given index onto a canvas, with its top left
+    /// corner at the given position.
+    ///
+    /// Parts of the frame outside of the canvas are clipped.
+    ///
+    /// # Example
+    /// ```
+    /// use pixel_loop::canvas::{Canvas, InMemoryCanvas};
+    /// use pixel_loop::color::Color;
+    /// use pixel_loop::sprite::{Flip, SpriteRect, SpriteSheet};
+    ///
+    /// let black = Color::from_rgb(0, 0, 0);
+    /// let white = Color::from_rgb(255, 255, 255);
+    ///
+    /// // A 3x1 arrow, whose tip is white
+    /// let mut image = InMemoryCanvas::new(3, 1, &black);
+    /// image.set(2, 0, &white);
+    /// let sheet = SpriteSheet::from_rects(image, vec![SpriteRect::new(0, 0, 3, 1)])?;
+    ///
+    /// let mut screen = InMemoryCanvas::new(4, 4, &black);
+    /// sheet.draw_frame(&mut screen, 0, 0, 0, Flip::Horizontal);
+    /// assert_eq!(screen.get(0, 0), &white);
+    /// assert_eq!(screen.get(2, 0), &black);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if no frame with the given index exists.
+    pub fn draw_frame<C: Canvas>(&self, canvas: &mut C, index: usize, x: i64, y: i64, flip: Flip) {
+        let frame = self.frames[index];
+        let Some((dst_x, dst_y, width, height)) = canvas.clip_rect(x, y, frame.width, frame.height)
+        else {
+            return;
+        };
+
+        // Offset of the visible part within the frame, if it is clipped at
+        // the top or left edge of the canvas.
+        let offset_x = (dst_x as i64 - x) as u32;
+        let offset_y = (dst_y as i64 - y) as u32;
+
+        let mut row = Vec::with_capacity(width as usize);
+        for dy in 0..height {
+            let frame_y = offset_y + dy;
+            let src_y = if flip.is_vertical() {
+                frame.y + frame.height - 1 - frame_y
+            } else {
+                frame.y + frame_y
+            };
+
+            let src_start = (src_y * self.canvas.width() + frame.x) as usize;
+            let src_row = self
+                .canvas
+                .get_range(src_start..src_start + frame.width as usize);
+            let visible = offset_x as usize..(offset_x + width) as usize;
+            row.clear();
+            if flip.is_horizontal() {
+                row.extend(src_row.iter().rev().skip(visible.start).take(visible.len()));
+            } else {
+                row.extend_from_slice(&src_row[visible]);
+            }
+
+            let dst_start = ((dst_y + dy) * canvas.width() + dst_x) as usize;
+            canvas.set_range_blended(dst_start..dst_start + width as usize, &row, self.blend);
+        }
+    }
+}
+
+/// How an [Animation] continues after its last frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AnimationMode {
+    /// Start over with the first frame
+    #[default]
+    Loop,
+    /// Play the frames backwards down to the first one, then forwards again
+    PingPong,
+    /// Stop at the last frame
+    Once,
+}
+
+/// A sequence of frames of a [SpriteSheet], each one shown for a certain
+/// duration.
+///
+/// # Example
+/// ```
+/// use pixel_loop::sprite::{Animation, AnimationMode};
+/// use std::time::Duration;
+///
+/// let mut blink = Animation::new([4, 5, 6], Duration::from_millis(100))
+///     .with_mode(AnimationMode::PingPong);
+///
+/// let mut frames = vec![];
+/// for _ in 0..6 {
+///     frames.push(blink.frame());
+///     blink.advance(Duration::from_millis(100));
+/// }
+/// assert_eq!(frames, vec![4, 5, 6, 5, 4, 5]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Animation {
+    frames: Vec<(usize, Duration)>,
+    mode: AnimationMode,
+    elapsed: Duration,
+}
+
+impl Animation {
+    /// Creates a new looping animation, which shows each of the given frames
+    /// for the same duration.
+    ///
+    /// # Arguments
+    /// * `frames` - Indices of the frames within the sprite sheet
+    /// * `frame_duration` - Duration each frame is shown
+    ///
+    /// # Panics
+    /// Panics if no frames are given.
+    pub fn new<F: IntoIterator<Item = usize>>(frames: F, frame_duration: Duration) -> Self {
+        Self::from_frames(frames.into_iter().map(|frame| (frame, frame_duration)))
+    }
+
+    /// Creates a new looping animation, with an individual duration for each
+    /// frame.
+    ///
+    /// # Arguments
+    /// * `frames` - Indices of the frames within the sprite sheet, together
+    ///   with the duration they are shown
+    ///
+    /// # Panics
+    /// Panics if no frames are given.
+    pub fn from_frames<F: IntoIterator<Item = (usize, Duration)>>(frames: F) -> Self {
+        let frames: Vec<_> = frames.into_iter().collect();
+        assert!(!frames.is_empty(), "An animation needs at least one frame");
+        Self {
+            frames,
+            mode: AnimationMode::default(),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Sets how the animation continues after its last frame.
+    ///
+    /// Defaults to [AnimationMode::Loop].
+    pub fn with_mode(mut self, mode: AnimationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Get how the animation continues after its last frame.
+    pub fn mode(&self) -> AnimationMode {
+        self.mode
+    }
+
+    /// Get the time elapsed since the animation has been started.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Advances the animation by the given time.
+    ///
+    /// Usually this is the time elapsed since the last rendered frame
+    /// ([FrameInfo::dt](crate::FrameInfo::dt)).
+    pub fn advance(&mut self, dt: Duration) {
+        self.elapsed += dt;
+    }
+
+    /// Restarts the animation from its first frame.
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// Check if an animation played [once](AnimationMode::Once) has reached
+    /// its end.
+    ///
+    /// Looping animations never finish.
+    pub fn is_finished(&self) -> bool {
+        self.mode == AnimationMode::Once && self.elapsed >= self.cycle_duration()
+    }
+
+    /// Get the index of the current frame within the sprite sheet.
+    pub fn frame(&self) -> usize {
+        let positions = self.cycle_positions();
+        let cycle_duration = self.duration_of(&positions).as_nanos();
+        let last = *positions.last().expect("animation to have frames");
+        let finished =
+            self.mode == AnimationMode::Once && self.elapsed.as_nanos() >= cycle_duration;
+        if cycle_duration == 0 || finished {
+            return self.frames[last].0;
+        }
+
+        let mut time = self.elapsed.as_nanos() % cycle_duration;
+        for position in positions {
+            let (frame, duration) = self.frames[position];
+            if time < duration.as_nanos() {
+                return frame;
+            }
+            time -= duration.as_nanos();
+        }
+        self.frames[last].0
+    }
+
+    /// Positions within the list of frames, which are played in one cycle of
+    /// the animation.
+    fn cycle_positions(&self) -> Vec<usize> {
+        let count = self.frames.len();
+        match self.mode {
+            AnimationMode::Loop | AnimationMode::Once => (0..count).collect(),
+            AnimationMode::PingPong => (0..count).chain((1..count - 1).rev()).collect(),
+        }
+    }
+
+    /// Total duration of the frames at the given positions.
+    fn duration_of(&self, positions: &[usize]) -> Duration {
+        positions
+            .iter()
+            .map(|&position| self.frames[position].1)
+            .sum()
+    }
+
+    fn cycle_duration(&self) -> Duration {
+        self.duration_of(&self.cycle_positions())
+    }
+}
+
+/// Advances an animation by the given time and draws its current frame onto
+/// a canvas, with its top left corner at the given position.
+///
+/// This is meant to be called once per rendered frame, with the time elapsed
+/// since the last one ([FrameInfo::dt](crate::FrameInfo::dt)).
+///
+/// # Panics
+/// Panics if the current frame of the animation does not exist in the sprite
+/// sheet.
+pub fn draw_sprite<C: Canvas>(
+    canvas: &mut C,
+    sheet: &SpriteSheet,
+    animation: &mut Animation,
+    dt: Duration,
+    x: i64,
+    y: i64,
+    flip: Flip,
+) {
+    animation.advance(dt);
+    sheet.draw_frame(canvas, animation.frame(), x, y, flip);
+}
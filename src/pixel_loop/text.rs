@@ -0,0 +1,782 @@
+//! Bitmap font text rendering.
+//!
+//! A [Font] stores the images of its glyphs in an [InMemoryCanvas] (atlas).
+//! Glyphs are drawn by blitting them from the atlas, tinted with the color of
+//! the text, so fonts are expected to consist of white glyphs on a
+//! transparent background. Fonts can be created from:
+//!
+//! * The built-in 5x8 pixel font covering printable ASCII ([Font::builtin])
+//! * An image containing the glyphs in a regular grid ([Font::from_grid])
+//! * PC Screen Font files (PSF1 and PSF2) as used by the linux console
+//!   ([Font::from_psf])
+//! * AngelCode BMFont descriptors in text format together with their page
+//!   image ([Font::from_bmfont])
+//!
+//! Text is drawn using [draw_text] and measured using [measure_text], which
+//! both take a [TextStyle] defining color, alignment and wrapping.
+//!
+//! # Example
+//! ```
+//! use pixel_loop::canvas::{Canvas, InMemoryCanvas};
+//! use pixel_loop::color::Color;
+//! use pixel_loop::text::{draw_text, measure_text, Font, TextAlign, TextStyle};
+//!
+//! let font = Font::builtin();
+//! let white = Color::from_rgb(255, 255, 255);
+//! let mut canvas = InMemoryCanvas::new(64, 32, &Color::from_rgb(0, 0, 0));
+//!
+//! let style = TextStyle::new(white).with_align(TextAlign::Center);
+//! assert_eq!(measure_text(&font, "Score", &style), (29, 9));
+//!
+//! // Centered around x = 32
+//! draw_text(&mut canvas, &font, "Score", 32, 4, &style);
+//! assert_eq!(canvas.get(18, 5), &white);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::canvas::{Canvas, InMemoryCanvas};
+use crate::color::{BlendMode, Color};
+use crate::sprite::SpriteRect;
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::HashMap;
+
+/// Glyphs of the built-in font for the characters `' '` to `'~'`.
+///
+/// Each glyph consists of 5 columns, whose bits represent its 8 rows from top
+/// (lowest bit) to bottom. The last row is only used by descenders.
+#[rustfmt::skip]
+const BUILTIN_GLYPHS: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], [0x00, 0x00, 0x5F, 0x00, 0x00], // ' ' '!'
+    [0x00, 0x07, 0x00, 0x07, 0x00], [0x14, 0x7F, 0x14, 0x7F, 0x14], // '"' '#'
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], [0x23, 0x13, 0x08, 0x64, 0x62], // '$' '%'
+    [0x36, 0x49, 0x55, 0x22, 0x50], [0x00, 0x05, 0x03, 0x00, 0x00], // '&' '''
+    [0x00, 0x1C, 0x22, 0x41, 0x00], [0x00, 0x41, 0x22, 0x1C, 0x00], // '(' ')'
+    [0x14, 0x08, 0x3E, 0x08, 0x14], [0x08, 0x08, 0x3E, 0x08, 0x08], // '*' '+'
+    [0x00, 0x80, 0x60, 0x00, 0x00], [0x08, 0x08, 0x08, 0x08, 0x08], // ',' '-'
+    [0x00, 0x60, 0x60, 0x00, 0x00], [0x20, 0x10, 0x08, 0x04, 0x02], // '.' '/'
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], [0x00, 0x42, 0x7F, 0x40, 0x00], // '0' '1'
+    [0x42, 0x61, 0x51, 0x49, 0x46], [0x21, 0x41, 0x45, 0x4B, 0x31], // '2' '3'
+    [0x18, 0x14, 0x12, 0x7F, 0x10], [0x27, 0x45, 0x45, 0x45, 0x39], // '4' '5'
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], [0x01, 0x71, 0x09, 0x05, 0x03], // '6' '7'
+    [0x36, 0x49, 0x49, 0x49, 0x36], [0x06, 0x49, 0x49, 0x29, 0x1E], // '8' '9'
+    [0x00, 0x36, 0x36, 0x00, 0x00], [0x00, 0x80, 0x66, 0x00, 0x00], // ':' ';'
+    [0x08, 0x14, 0x22, 0x41, 0x00], [0x14, 0x14, 0x14, 0x14, 0x14], // '<' '='
+    [0x00, 0x41, 0x22, 0x14, 0x08], [0x02, 0x01, 0x51, 0x09, 0x06], // '>' '?'
+    [0x32, 0x49, 0x79, 0x41, 0x3E], [0x7E, 0x11, 0x11, 0x11, 0x7E], // '@' 'A'
+    [0x7F, 0x49, 0x49, 0x49, 0x36], [0x3E, 0x41, 0x41, 0x41, 0x22], // 'B' 'C'
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], [0x7F, 0x49, 0x49, 0x49, 0x41], // 'D' 'E'
+    [0x7F, 0x09, 0x09, 0x09, 0x01], [0x3E, 0x41, 0x49, 0x49, 0x7A], // 'F' 'G'
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], [0x00, 0x41, 0x7F, 0x41, 0x00], // 'H' 'I'
+    [0x20, 0x40, 0x41, 0x3F, 0x01], [0x7F, 0x08, 0x14, 0x22, 0x41], // 'J' 'K'
+    [0x7F, 0x40, 0x40, 0x40, 0x40], [0x7F, 0x02, 0x0C, 0x02, 0x7F], // 'L' 'M'
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], [0x3E, 0x41, 0x41, 0x41, 0x3E], // 'N' 'O'
+    [0x7F, 0x09, 0x09, 0x09, 0x06], [0x3E, 0x41, 0x51, 0x21, 0x5E], // 'P' 'Q'
+    [0x7F, 0x09, 0x19, 0x29, 0x46], [0x46, 0x49, 0x49, 0x49, 0x31], // 'R' 'S'
+    [0x01, 0x01, 0x7F, 0x01, 0x01], [0x3F, 0x40, 0x40, 0x40, 0x3F], // 'T' 'U'
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], [0x3F, 0x40, 0x38, 0x40, 0x3F], // 'V' 'W'
+    [0x63, 0x14, 0x08, 0x14, 0x63], [0x07, 0x08, 0x70, 0x08, 0x07], // 'X' 'Y'
+    [0x61, 0x51, 0x49, 0x45, 0x43], [0x00, 0x7F, 0x41, 0x41, 0x00], // 'Z' '['
+    [0x02, 0x04, 0x08, 0x10, 0x20], [0x00, 0x41, 0x41, 0x7F, 0x00], // '\' ']'
+    [0x04, 0x02, 0x01, 0x02, 0x04], [0x40, 0x40, 0x40, 0x40, 0x40], // '^' '_'
+    [0x00, 0x01, 0x02, 0x04, 0x00], [0x20, 0x54, 0x54, 0x54, 0x78], // '`' 'a'
+    [0x7F, 0x48, 0x44, 0x44, 0x38], [0x38, 0x44, 0x44, 0x44, 0x20], // 'b' 'c'
+    [0x38, 0x44, 0x44, 0x48, 0x7F], [0x38, 0x54, 0x54, 0x54, 0x18], // 'd' 'e'
+    [0x08, 0x7E, 0x09, 0x01, 0x02], [0x18, 0xA4, 0xA4, 0xA4, 0x7C], // 'f' 'g'
+    [0x7F, 0x08, 0x04, 0x04, 0x78], [0x00, 0x44, 0x7D, 0x40, 0x00], // 'h' 'i'
+    [0x40, 0x80, 0x84, 0x7D, 0x00], [0x7F, 0x10, 0x28, 0x44, 0x00], // 'j' 'k'
+    [0x00, 0x41, 0x7F, 0x40, 0x00], [0x7C, 0x04, 0x18, 0x04, 0x78], // 'l' 'm'
+    [0x7C, 0x08, 0x04, 0x04, 0x78], [0x38, 0x44, 0x44, 0x44, 0x38], // 'n' 'o'
+    [0xFC, 0x24, 0x24, 0x24, 0x18], [0x18, 0x24, 0x24, 0x24, 0xFC], // 'p' 'q'
+    [0x7C, 0x08, 0x04, 0x04, 0x08], [0x48, 0x54, 0x54, 0x54, 0x20], // 'r' 's'
+    [0x04, 0x3F, 0x44, 0x40, 0x20], [0x3C, 0x40, 0x40, 0x20, 0x7C], // 't' 'u'
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], [0x3C, 0x40, 0x30, 0x40, 0x3C], // 'v' 'w'
+    [0x44, 0x28, 0x10, 0x28, 0x44], [0x9C, 0xA0, 0xA0, 0xA0, 0x7C], // 'x' 'y'
+    [0x44, 0x64, 0x54, 0x4C, 0x44], [0x00, 0x08, 0x36, 0x41, 0x00], // 'z' '{'
+    [0x00, 0x00, 0x7F, 0x00, 0x00], [0x00, 0x41, 0x36, 0x08, 0x00], // '|' '}'
+    [0x08, 0x04, 0x08, 0x10, 0x08],                                 // '~'
+];
+
+/// Placement of a single glyph of a [Font].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Glyph {
+    /// Region of the glyph within the atlas of the font
+    pub rect: SpriteRect,
+    /// Offset of the glyph image from the current pen position (x) and the
+    /// top of the line (y)
+    pub offset: (i32, i32),
+    /// Horizontal distance the pen moves after drawing the glyph
+    pub advance: i32,
+}
+
+/// A bitmap font, whose glyphs are stored in an [InMemoryCanvas].
+#[derive(Clone)]
+pub struct Font {
+    atlas: InMemoryCanvas,
+    glyphs: HashMap<char, Glyph>,
+    kerning: HashMap<(char, char), i32>,
+    line_height: u32,
+    fallback: Option<char>,
+}
+
+impl Font {
+    /// Creates a font from an atlas and its glyphs.
+    ///
+    /// # Arguments
+    /// * `atlas` - Image containing all glyphs
+    /// * `glyphs` - Placement of each character within the atlas
+    /// * `line_height` - Vertical distance between two lines of text
+    pub fn new(atlas: InMemoryCanvas, glyphs: HashMap<char, Glyph>, line_height: u32) -> Self {
+        let fallback = glyphs.contains_key(&'?').then_some('?');
+        Self {
+            atlas,
+            glyphs,
+            kerning: HashMap::new(),
+            line_height,
+            fallback,
+        }
+    }
+
+    /// Creates the built-in font, covering printable ASCII characters with
+    /// glyphs of 5x8 pixels and a line height of 9 pixels.
+    pub fn builtin() -> Self {
+        let transparent = Color::from_rgba(0, 0, 0, 0);
+        let white = Color::from_rgb(255, 255, 255);
+        let mut atlas = InMemoryCanvas::new(BUILTIN_GLYPHS.len() as u32 * 5, 8, &transparent);
+        let mut glyphs = HashMap::new();
+
+        for (index, columns) in BUILTIN_GLYPHS.iter().enumerate() {
+            let left = index as u32 * 5;
+            for (x, column) in columns.iter().enumerate() {
+                for y in 0..8 {
+                    if column & (1 << y) != 0 {
+                        atlas.set(left + x as u32, y, &white);
+                    }
+                }
+            }
+            glyphs.insert(
+                char::from(b' ' + index as u8),
+                Glyph {
+                    rect: SpriteRect::new(left, 0, 5, 8),
+                    offset: (0, 0),
+                    advance: 6,
+                },
+            );
+        }
+
+        Self::new(atlas, glyphs, 9)
+    }
+
+    /// Creates a font from an image, which contains equally sized glyphs in a
+    /// regular grid.
+    ///
+    /// Glyphs are assigned to the given characters row by row, from left to
+    /// right. Each glyph advances the pen by its width.
+    ///
+    /// # Errors
+    /// Returns an error if the glyph size is zero or the image does not
+    /// contain enough glyphs for all characters.
+    pub fn from_grid(
+        atlas: InMemoryCanvas,
+        glyph_width: u32,
+        glyph_height: u32,
+        characters: &str,
+    ) -> Result<Self> {
+        if glyph_width == 0 || glyph_height == 0 {
+            bail!("Glyphs need to be at least 1x1 pixels, got {glyph_width}x{glyph_height}");
+        }
+        let columns = atlas.width() / glyph_width;
+        let capacity = columns * (atlas.height() / glyph_height);
+        let count = characters.chars().count();
+        if count > capacity as usize {
+            bail!(
+                "Font image of {}x{} holds {capacity} glyphs of {glyph_width}x{glyph_height}, but {count} characters are given",
+                atlas.width(),
+                atlas.height()
+            );
+        }
+
+        let glyphs = characters
+            .chars()
+            .enumerate()
+            .map(|(index, character)| {
+                let (column, row) = (index as u32 % columns, index as u32 / columns);
+                let glyph = Glyph {
+                    rect: SpriteRect::new(
+                        column * glyph_width,
+                        row * glyph_height,
+                        glyph_width,
+                        glyph_height,
+                    ),
+                    offset: (0, 0),
+                    advance: glyph_width as i32,
+                };
+                (character, glyph)
+            })
+            .collect();
+
+        Ok(Self::new(atlas, glyphs, glyph_height))
+    }
+
+    /// Creates a font from a PC Screen Font (PSF1 or PSF2) file.
+    ///
+    /// If the font contains a unicode table, glyphs are assigned to the
+    /// characters listed there. Otherwise glyph `n` is assigned to the
+    /// character with code point `n`.
+    ///
+    /// # Errors
+    /// Returns an error if the data is not a valid PSF font.
+    ///
+    /// # Examples
+    /// ```
+    /// use pixel_loop::canvas::Canvas;
+    /// use pixel_loop::text::Font;
+    ///
+    /// // PSF1 font with 256 glyphs of 8x2 pixels, whose glyph for 'A' is a
+    /// // bar in the top row.
+    /// let mut psf = vec![0x36, 0x04, 0x00, 2];
+    /// psf.extend((0..256).flat_map(|glyph| if glyph == 65 { [0xFF, 0x00] } else { [0x00, 0x00] }));
+    ///
+    /// let font = Font::from_psf(&psf)?;
+    /// assert_eq!(font.line_height(), 2);
+    /// let glyph = font.glyph('A').expect("glyph");
+    /// assert_eq!(font.atlas().get(glyph.rect.x + 7, glyph.rect.y).a, 255);
+    /// assert_eq!(font.atlas().get(glyph.rect.x, glyph.rect.y + 1).a, 0);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn from_psf(bytes: &[u8]) -> Result<Self> {
+        let psf = match bytes {
+            [0x36, 0x04, mode, height, data @ ..] => {
+                let count = if mode & 0x01 != 0 { 512 } else { 256 };
+                let glyph_size = *height as usize;
+                let has_table = mode & 0x06 != 0;
+                Psf {
+                    width: 8,
+                    height: *height as u32,
+                    count,
+                    glyph_size,
+                    bitmaps: data,
+                    table: has_table.then(|| &data[(count * glyph_size).min(data.len())..]),
+                    table_format: PsfTableFormat::Psf1,
+                }
+            }
+            [0x72, 0xb5, 0x4a, 0x86, ..] => {
+                let field = |index: usize| -> Result<usize> {
+                    let start = 4 + index * 4;
+                    let bytes = bytes
+                        .get(start..start + 4)
+                        .context("PSF2 header is truncated")?;
+                    Ok(u32::from_le_bytes(bytes.try_into()?) as usize)
+                };
+                let (header_size, flags, count) = (field(1)?, field(2)?, field(3)?);
+                let (glyph_size, height, width) = (field(4)?, field(5)?, field(6)?);
+                if glyph_size < height * width.div_ceil(8) {
+                    bail!("PSF2 glyphs of {glyph_size} bytes can not hold {width}x{height} pixels");
+                }
+                let data = bytes
+                    .get(header_size..)
+                    .context("PSF2 header is truncated")?;
+                Psf {
+                    width: width as u32,
+                    height: height as u32,
+                    count,
+                    glyph_size,
+                    bitmaps: data,
+                    table: (flags & 0x01 != 0)
+                        .then(|| &data[(count * glyph_size).min(data.len())..]),
+                    table_format: PsfTableFormat::Psf2,
+                }
+            }
+            _ => bail!("Data is no PSF font"),
+        };
+        psf.into_font()
+    }
+
+    /// Creates a font from an AngelCode BMFont descriptor in text format
+    /// (`.fnt`) and the image of its page.
+    ///
+    /// Only fonts with a single page are supported. Kerning pairs are
+    /// respected.
+    ///
+    /// # Errors
+    /// Returns an error if the descriptor can not be parsed, refers to more
+    /// than one page, or places glyphs outside of the page.
+    ///
+    /// # Examples
+    /// ```
+    /// use pixel_loop::canvas::InMemoryCanvas;
+    /// use pixel_loop::color::Color;
+    /// use pixel_loop::text::{measure_text, Font, TextStyle};
+    ///
+    /// let descriptor = r#"
+    /// info face="Tiny" size=4
+    /// common lineHeight=5 base=4 scaleW=8 scaleH=4 pages=1
+    /// page id=0 file="tiny.png"
+    /// chars count=2
+    /// char id=65 x=0 y=0 width=3 height=4 xoffset=0 yoffset=1 xadvance=4 page=0
+    /// char id=86 x=4 y=0 width=3 height=4 xoffset=0 yoffset=1 xadvance=4 page=0
+    /// kernings count=1
+    /// kerning first=65 second=86 amount=-1
+    /// "#;
+    /// let page = InMemoryCanvas::new(8, 4, &Color::from_rgb(255, 255, 255));
+    /// let font = Font::from_bmfont(descriptor, page)?;
+    ///
+    /// let style = TextStyle::new(Color::from_rgb(255, 255, 255));
+    /// assert_eq!(measure_text(&font, "AV", &style), (6, 5));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn from_bmfont(descriptor: &str, page: InMemoryCanvas) -> Result<Self> {
+        let mut line_height = None;
+        let mut glyphs = HashMap::new();
+        let mut kerning = HashMap::new();
+
+        for (number, line) in descriptor.lines().enumerate() {
+            let mut parts = line.split_whitespace();
+            let Some(tag) = parts.next() else {
+                continue;
+            };
+            let attributes: HashMap<&str, &str> = parts
+                .filter_map(|part| part.split_once('='))
+                .map(|(key, value)| (key, value.trim_matches('"')))
+                .collect();
+            let attribute = |name: &str| -> Result<i32> {
+                let value = attributes
+                    .get(name)
+                    .with_context(|| format!("missing attribute {name} in line {}", number + 1))?;
+                value
+                    .parse()
+                    .with_context(|| format!("invalid attribute {name} in line {}", number + 1))
+            };
+            let character = |name: &str| -> Result<char> {
+                let code = attribute(name)?;
+                char::from_u32(code as u32)
+                    .ok_or_else(|| anyhow!("invalid character {code} in line {}", number + 1))
+            };
+
+            match tag {
+                "common" => {
+                    line_height = Some(attribute("lineHeight")? as u32);
+                    if attributes.contains_key("pages") && attribute("pages")? > 1 {
+                        bail!("BMFont with multiple pages is not supported");
+                    }
+                }
+                "char" => {
+                    let rect = SpriteRect::new(
+                        attribute("x")? as u32,
+                        attribute("y")? as u32,
+                        attribute("width")? as u32,
+                        attribute("height")? as u32,
+                    );
+                    if rect.x as u64 + rect.width as u64 > page.width() as u64
+                        || rect.y as u64 + rect.height as u64 > page.height() as u64
+                    {
+                        bail!(
+                            "Glyph in line {} is out of bounds of page of {}x{}",
+                            number + 1,
+                            page.width(),
+                            page.height()
+                        );
+                    }
+                    let glyph = Glyph {
+                        rect,
+                        offset: (attribute("xoffset")?, attribute("yoffset")?),
+                        advance: attribute("xadvance")?,
+                    };
+                    glyphs.insert(character("id")?, glyph);
+                }
+                "kerning" => {
+                    kerning.insert(
+                        (character("first")?, character("second")?),
+                        attribute("amount")?,
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        let line_height = line_height.context("BMFont descriptor has no common line")?;
+        let mut font = Self::new(page, glyphs, line_height);
+        font.kerning = kerning;
+        Ok(font)
+    }
+
+    /// Sets the vertical distance between two lines of text.
+    pub fn with_line_height(mut self, line_height: u32) -> Self {
+        self.line_height = line_height;
+        self
+    }
+
+    /// Sets the character drawn instead of characters without a glyph, or
+    /// disables drawing those.
+    ///
+    /// Defaults to `'?'`, if the font contains it.
+    pub fn with_fallback(mut self, fallback: Option<char>) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Get the vertical distance between two lines of text.
+    pub fn line_height(&self) -> u32 {
+        self.line_height
+    }
+
+    /// Get the image containing all glyphs.
+    pub fn atlas(&self) -> &InMemoryCanvas {
+        &self.atlas
+    }
+
+    /// Get the glyph of the given character, if the font contains it.
+    pub fn glyph(&self, character: char) -> Option<&Glyph> {
+        self.glyphs.get(&character)
+    }
+
+    /// Get the glyph drawn for the given character, taking the fallback into
+    /// account.
+    fn resolve(&self, character: char) -> Option<(char, &Glyph)> {
+        match self.glyphs.get(&character) {
+            Some(glyph) => Some((character, glyph)),
+            None => {
+                let fallback = self.fallback?;
+                self.glyphs.get(&fallback).map(|glyph| (fallback, glyph))
+            }
+        }
+    }
+
+    /// Lays out a single line of text, returning the pen position of every
+    /// drawn glyph together with the visible width of the line.
+    fn layout_line<'a>(&'a self, line: &str) -> (Vec<(i64, &'a Glyph)>, u32) {
+        let mut pen = 0_i64;
+        let mut width = 0_i64;
+        let mut previous = None;
+        let mut placed = vec![];
+
+        for character in line.chars() {
+            let Some((character, glyph)) = self.resolve(character) else {
+                continue;
+            };
+            if let Some(previous) = previous {
+                pen += *self.kerning.get(&(previous, character)).unwrap_or(&0) as i64;
+            }
+            if glyph.rect.width > 0 && glyph.rect.height > 0 {
+                width = width.max(pen + glyph.offset.0 as i64 + glyph.rect.width as i64);
+                placed.push((pen, glyph));
+            }
+            pen += glyph.advance as i64;
+            previous = Some(character);
+        }
+
+        (placed, width.max(0) as u32)
+    }
+
+    /// Visible width of a single line of text.
+    fn line_width(&self, line: &str) -> u32 {
+        self.layout_line(line).1
+    }
+
+    /// Splits the text into the lines it is drawn in, wrapping them at the
+    /// given width.
+    fn wrap<'t>(&self, text: &'t str, max_width: Option<u32>) -> Vec<&'t str> {
+        let Some(max_width) = max_width else {
+            return text.split('\n').collect();
+        };
+
+        let mut lines = vec![];
+        for paragraph in text.split('\n') {
+            let mut rest = paragraph;
+            loop {
+                if self.line_width(rest) <= max_width {
+                    lines.push(rest);
+                    break;
+                }
+
+                // Break after the last word fitting into the line, or within
+                // the first word, if it does not fit on its own.
+                let mut end = None;
+                for (index, _) in rest.match_indices(' ') {
+                    if self.line_width(&rest[..index]) > max_width {
+                        break;
+                    }
+                    end = Some((index, index + 1));
+                }
+                let (end, next) = end.unwrap_or_else(|| {
+                    let mut end = rest
+                        .char_indices()
+                        .skip(1)
+                        .map(|(index, _)| index)
+                        .take_while(|&index| self.line_width(&rest[..index]) <= max_width)
+                        .last()
+                        .unwrap_or(0);
+                    if end == 0 {
+                        // At least one character per line
+                        end = rest.chars().next().map_or(rest.len(), char::len_utf8);
+                    }
+                    (end, end)
+                });
+
+                lines.push(rest[..end].trim_end_matches(' '));
+                rest = rest[next..].trim_start_matches(' ');
+                if rest.is_empty() {
+                    break;
+                }
+            }
+        }
+        lines
+    }
+}
+
+/// Format of the unicode table of a PSF font.
+enum PsfTableFormat {
+    /// Little endian UCS-2 code points
+    Psf1,
+    /// UTF-8 encoded characters
+    Psf2,
+}
+
+/// Raw contents of a PSF font.
+struct Psf<'a> {
+    width: u32,
+    height: u32,
+    count: usize,
+    glyph_size: usize,
+    bitmaps: &'a [u8],
+    table: Option<&'a [u8]>,
+    table_format: PsfTableFormat,
+}
+
+impl Psf<'_> {
+    fn into_font(self) -> Result<Font> {
+        if self.width == 0 || self.height == 0 || self.count == 0 {
+            bail!("PSF font does not contain any glyphs");
+        }
+        if self.bitmaps.len() < self.count * self.glyph_size {
+            bail!(
+                "PSF font is truncated: {} glyphs of {} bytes expected",
+                self.count,
+                self.glyph_size
+            );
+        }
+
+        // Glyphs are stored in a single row within the atlas.
+        let transparent = Color::from_rgba(0, 0, 0, 0);
+        let white = Color::from_rgb(255, 255, 255);
+        let mut atlas =
+            InMemoryCanvas::new(self.width * self.count as u32, self.height, &transparent);
+        let row_size = self.width.div_ceil(8) as usize;
+        for index in 0..self.count {
+            let bitmap = &self.bitmaps[index * self.glyph_size..(index + 1) * self.glyph_size];
+            for y in 0..self.height {
+                let row = &bitmap[y as usize * row_size..(y as usize + 1) * row_size];
+                for x in 0..self.width {
+                    if row[x as usize / 8] & (0x80 >> (x % 8)) != 0 {
+                        atlas.set(index as u32 * self.width + x, y, &white);
+                    }
+                }
+            }
+        }
+
+        let glyph = |index: usize| Glyph {
+            rect: SpriteRect::new(index as u32 * self.width, 0, self.width, self.height),
+            offset: (0, 0),
+            advance: self.width as i32,
+        };
+        let mut glyphs = HashMap::new();
+        match self.table {
+            Some(table) => {
+                for (index, characters) in self.table_entries(table)?.into_iter().enumerate() {
+                    for character in characters {
+                        glyphs.entry(character).or_insert_with(|| glyph(index));
+                    }
+                }
+            }
+            None => {
+                for index in 0..self.count {
+                    if let Some(character) = char::from_u32(index as u32) {
+                        glyphs.insert(character, glyph(index));
+                    }
+                }
+            }
+        }
+
+        Ok(Font::new(atlas, glyphs, self.height))
+    }
+
+    /// Characters assigned to each glyph by the unicode table.
+    ///
+    /// Sequences of combining characters are not supported and skipped.
+    fn table_entries(&self, table: &[u8]) -> Result<Vec<Vec<char>>> {
+        let mut entries = Vec::with_capacity(self.count);
+        let mut rest = table;
+        while entries.len() < self.count && !rest.is_empty() {
+            let mut characters = vec![];
+            let mut in_sequence = false;
+            match self.table_format {
+                PsfTableFormat::Psf1 => loop {
+                    let [low, high, tail @ ..] = rest else {
+                        bail!("PSF1 unicode table is truncated");
+                    };
+                    rest = tail;
+                    match u16::from_le_bytes([*low, *high]) {
+                        0xFFFF => break,
+                        0xFFFE => in_sequence = true,
+                        code if !in_sequence => characters.extend(char::from_u32(code as u32)),
+                        _ => {}
+                    }
+                },
+                PsfTableFormat::Psf2 => {
+                    let end = rest
+                        .iter()
+                        .position(|&byte| byte == 0xFF)
+                        .context("PSF2 unicode table is truncated")?;
+                    let (entry, tail) = (&rest[..end], &rest[end + 1..]);
+                    rest = tail;
+                    // Everything after the first 0xFE are sequences.
+                    let single = entry.split(|&byte| byte == 0xFE).next().unwrap_or(&[]);
+                    characters.extend(String::from_utf8_lossy(single).chars());
+                }
+            }
+            entries.push(characters);
+        }
+        Ok(entries)
+    }
+}
+
+/// Horizontal alignment of text relative to the position it is drawn at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextAlign {
+    /// Lines start at the position
+    #[default]
+    Left,
+    /// Lines are centered around the position
+    Center,
+    /// Lines end at the position
+    Right,
+}
+
+/// Appearance and layout of drawn text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextStyle {
+    color: Color,
+    align: TextAlign,
+    max_width: Option<u32>,
+    blend: BlendMode,
+}
+
+impl TextStyle {
+    /// Creates a new style for left aligned text in the given color, which is
+    /// not wrapped.
+    pub fn new(color: Color) -> Self {
+        Self {
+            color,
+            align: TextAlign::default(),
+            max_width: None,
+            blend: BlendMode::Alpha,
+        }
+    }
+
+    /// Sets the color of the text.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets the horizontal alignment of the text.
+    ///
+    /// Defaults to [TextAlign::Left].
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Wraps lines exceeding the given width in pixels.
+    ///
+    /// Lines are wrapped at spaces, or within words, which do not fit into a
+    /// line on their own.
+    pub fn with_max_width(mut self, max_width: u32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Sets how the glyphs are combined with the pixels of the canvas.
+    ///
+    /// Defaults to [BlendMode::Alpha].
+    pub fn with_blend_mode(mut self, blend: BlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    /// Get the color of the text.
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    /// Get the horizontal alignment of the text.
+    pub fn align(&self) -> TextAlign {
+        self.align
+    }
+
+    /// Get the width at which lines are wrapped, if any.
+    pub fn max_width(&self) -> Option<u32> {
+        self.max_width
+    }
+}
+
+/// Measures the size of the given text in pixels (width, height), as it
+/// would be drawn by [draw_text].
+///
+/// The width is the visible width of the longest line, while the height is the
+/// number of lines multiplied by the line height of the font.
+pub fn measure_text(font: &Font, text: &str, style: &TextStyle) -> (u32, u32) {
+    let lines = font.wrap(text, style.max_width);
+    let width = lines
+        .iter()
+        .map(|line| font.line_width(line))
+        .max()
+        .unwrap_or(0);
+    (width, lines.len() as u32 * font.line_height)
+}
+
+/// Draws text onto a canvas with the top of its first line at the given
+/// position.
+///
+/// Depending on the alignment of the style, `x` is the left edge, the center
+/// or the right edge of every line. Lines are separated by `'\n'`, and wrapped
+/// if the style defines a maximum width. Parts of the text outside of the
+/// canvas are clipped.
+///
+/// # Returns
+/// The size of the drawn text (See [measure_text]).
+pub fn draw_text<C: Canvas>(
+    canvas: &mut C,
+    font: &Font,
+    text: &str,
+    x: i64,
+    y: i64,
+    style: &TextStyle,
+) -> (u32, u32) {
+    let lines = font.wrap(text, style.max_width);
+    let mut width = 0;
+    for (number, line) in lines.iter().enumerate() {
+        let (placed, line_width) = font.layout_line(line);
+        width = width.max(line_width);
+
+        let left = match style.align {
+            TextAlign::Left => x,
+            TextAlign::Center => x - line_width as i64 / 2,
+            TextAlign::Right => x - line_width as i64,
+        };
+        let top = y + number as i64 * font.line_height as i64;
+        for (pen, glyph) in placed {
+            canvas.blit_rect_blended(
+                &font.atlas,
+                glyph.rect.x,
+                glyph.rect.y,
+                glyph.rect.width,
+                glyph.rect.height,
+                left + pen + glyph.offset.0 as i64,
+                top + glyph.offset.1 as i64,
+                Some(&style.color),
+                style.blend,
+            );
+        }
+    }
+    (width, lines.len() as u32 * font.line_height)
+}